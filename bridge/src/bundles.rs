@@ -0,0 +1,128 @@
+use near_sdk::{AccountId, Balance, env, near_bindgen, Promise, PromiseError, require};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupSet;
+use near_sdk::json_types::U128;
+use serde_json::json;
+
+use shared::{ACCOUNT_STORAGE_BYTES, CALL_GAS, GAS_FOR_TX, get_bundle_proxy_id};
+
+use crate::events_bundle::BundleExecutionData;
+
+use super::*;
+
+/// State written by `new` of the bundle proxy with 64 characters accounts, measured with the unit
+/// test environment.
+const BUNDLE_PROXY_STATE_BYTES: u64 = 181;
+
+/// Deployed bundle proxy accounts.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct BundleProxies {
+    pub proxies: LookupSet<AccountId>,
+}
+
+impl BundleProxies {
+    pub fn new() -> Self {
+        Self {
+            proxies: LookupSet::new(b"bundle_proxies".to_vec()),
+        }
+    }
+
+    pub fn is_deployed(&self, proxy: &AccountId) -> bool {
+        self.proxies.contains(proxy)
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    pub fn get_bundle_proxy_id(&self, receiver_id: AccountId, salt: String) -> AccountId {
+        get_bundle_proxy_id(&env::current_account_id(), &receiver_id, &salt)
+    }
+
+    pub fn is_bundle_proxy_deployed(&self, proxy: AccountId) -> bool {
+        self.bundle_proxies.is_deployed(&proxy)
+    }
+
+    /// Deploys the bundle proxy of the receiver and salt, it has to exist before a withdrawal with
+    /// the bundle is executed. Can be called by anyone, the attached deposit pays for the account,
+    /// the contract code and its state.
+    #[payable]
+    pub fn deploy_bundle_proxy(&mut self, receiver_id: AccountId, salt: String) -> Promise {
+        let proxy = get_bundle_proxy_id(&env::current_account_id(), &receiver_id, &salt);
+        require!(!self.bundle_proxies.is_deployed(&proxy), "Bundles: proxy already deployed");
+
+        let code = self.factory.get_proxy_code();
+        let deposit = env::attached_deposit();
+        let min_deposit = Balance::from(code.len() as u64 + BUNDLE_PROXY_STATE_BYTES + ACCOUNT_STORAGE_BYTES) * env::storage_byte_cost();
+        require!(
+            deposit >= min_deposit,
+            format!("Requires attached deposit of at least {} yoctoNEAR", min_deposit),
+        );
+
+        let args = json!({
+            "bridge": env::current_account_id(),
+            "owner_id": receiver_id,
+        });
+
+        Promise::new(proxy.clone())
+            .create_account()
+            .transfer(deposit)
+            .deploy_contract(code)
+            .function_call("new".to_string(), args.to_string().into_bytes(), 0, CALL_GAS)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TX)
+                    .deploy_bundle_proxy_callback(proxy, env::predecessor_account_id(), U128(deposit))
+            )
+    }
+
+    #[private]
+    pub fn deploy_bundle_proxy_callback(&mut self, proxy: AccountId, sender: AccountId, deposit: U128) {
+        if is_promise_succeeded(0) {
+            self.bundle_proxies.proxies.insert(&proxy);
+        } else {
+            // The failed receipt returns the deposit to the bridge, pass it back to the caller
+            Promise::new(sender).transfer(deposit.0);
+        }
+    }
+
+    #[private]
+    pub fn bundle_execute_callback(
+        &mut self,
+        #[callback_result] call_result: Result<bool, PromiseError>,
+        origin: String,
+        proxy: AccountId,
+        receiver: AccountId,
+    ) -> bool {
+        let is_executed = call_result.unwrap_or(false);
+        let data = vec![BundleExecutionData::new(&origin, &proxy, &receiver)];
+
+        if is_executed {
+            NearEvent::bundle_executed(data).emit();
+        } else {
+            NearEvent::bundle_failed(data).emit();
+        }
+
+        is_executed
+    }
+}
+
+impl Bridge {
+    /// Runs the bundle of the delivered withdrawal on its proxy. The bundle result does not
+    /// affect the withdrawal: on failure the assets stay on the proxy under the receiver control.
+    pub(crate) fn internal_execute_bundle(&self, withdrawal: &Withdrawal) {
+        let bundle = match withdrawal.bundle.clone() {
+            Some(bundle) => bundle,
+            None => return,
+        };
+        let proxy = bundle.get_proxy_id(&env::current_account_id(), &withdrawal.receiver_id);
+
+        ext_bundle_proxy::ext(proxy.clone())
+            .with_static_gas(bundle.required_gas())
+            .execute(bundle.calls.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TX)
+                    .bundle_execute_callback(withdrawal.origin.clone(), proxy, withdrawal.receiver_id.clone())
+            );
+    }
+}
@@ -0,0 +1,43 @@
+use near_sdk::{env, near_bindgen, Promise};
+
+use shared::{AddressFormat, AuthorizedOperation, ChainInfo, GAS_FOR_TX, OperationType, RecoverableSignature};
+
+use super::*;
+
+#[near_bindgen]
+impl Bridge {
+    pub fn get_chains(&self) -> Vec<ChainInfo> {
+        self.chains.get_chains()
+    }
+
+    /// Adds or updates the supported destination chain and forwards it to the fee contract.
+    pub fn set_chain(&mut self, chain: String, format: AddressFormat, signatures: Vec<RecoverableSignature>) -> Promise {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::SetChain).string(&chain).borsh(&format));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.internal_set_chain(chain, Some(format))
+    }
+
+    pub fn remove_chain(&mut self, chain: String, signatures: Vec<RecoverableSignature>) -> Promise {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::RemoveChain).string(&chain));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.internal_set_chain(chain, None)
+    }
+}
+
+impl Bridge {
+    fn internal_set_chain(&mut self, chain: String, format: Option<AddressFormat>) -> Promise {
+        self.chains.set_chain(&chain, format.clone());
+
+        ext_feer::ext(self.fee_contract.clone())
+            .with_static_gas(GAS_FOR_TX)
+            .set_chain(chain, format)
+    }
+
+    /// Panics if the chain is not supported or the receiver does not match its address format.
+    pub(crate) fn internal_normalize_receiver(&self, chain: &String, receiver: &str) -> String {
+        self.chains.normalize_receiver(chain, receiver)
+            .unwrap_or_else(|| env::panic_str("Chains: invalid receiver for the destination chain"))
+    }
+}
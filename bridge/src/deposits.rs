@@ -0,0 +1,114 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, env, near_bindgen};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::{U128, U64};
+use serde::{Deserialize, Serialize};
+
+use shared::Hash;
+
+use super::*;
+
+/// Deposit data committed to the accumulator, the leaf is the keccak256 of its Borsh encoding.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct Deposit {
+    pub nonce: u64,
+    pub token: Option<AccountId>,
+    pub token_id: Option<TokenId>,
+    pub amount: Option<u128>,
+    pub sender: AccountId,
+    pub receiver: String,
+    pub chain_to: String,
+    pub bundle_data: Option<String>,
+    pub bundle_salt: Option<String>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub struct DepositCommitment {
+    pub nonce: U64,
+    pub leaf: String,
+    // keccak256(previous accumulator || leaf), the previous accumulator of the first deposit is zero
+    pub accumulator: String,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct DepositsAccumulator {
+    pub count: u64,
+    pub accumulator: Hash,
+    pub commitments: LookupMap<u64, DepositCommitment>,
+}
+
+impl DepositsAccumulator {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            accumulator: Hash::new(),
+            commitments: LookupMap::new(b"deposit_commitments".to_vec()),
+        }
+    }
+
+    pub fn next_nonce(&self) -> u64 {
+        self.count
+    }
+
+    /// Appends the deposit to the hash chain and returns its nonce.
+    pub fn commit(&mut self, deposit: &Deposit) -> u64 {
+        assert_eq!(deposit.nonce, self.count, "Deposits: unexpected deposit nonce");
+
+        let leaf = Hash::from_slice(env::keccak256(deposit.try_to_vec().unwrap().as_slice()).as_slice());
+        self.accumulator = Hash::from_slice(
+            env::keccak256([self.accumulator.to_slice(), leaf.to_slice()].concat().as_slice()).as_slice()
+        );
+
+        self.commitments.insert(&self.count, &DepositCommitment {
+            nonce: U64(self.count),
+            leaf: leaf.as_str(),
+            accumulator: self.accumulator.as_str(),
+        });
+        self.count += 1;
+
+        deposit.nonce
+    }
+
+    pub fn get_commitment(&self, nonce: u64) -> Option<DepositCommitment> {
+        self.commitments.get(&nonce)
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    pub fn get_deposit_count(&self) -> U64 {
+        U64(self.deposits.count)
+    }
+
+    /// Returns the leaf and the accumulator after the deposit with the given nonce.
+    pub fn get_deposit_commitment(&self, n: U64) -> Option<DepositCommitment> {
+        self.deposits.get_commitment(n.0)
+    }
+}
+
+impl Bridge {
+    pub(crate) fn internal_commit_deposit(
+        &mut self,
+        token: Option<AccountId>,
+        token_id: Option<TokenId>,
+        amount: Option<U128>,
+        sender: AccountId,
+        receiver: String,
+        chain_to: String,
+        bundle_data: Option<String>,
+        bundle_salt: Option<String>,
+    ) -> u64 {
+        self.deposits.commit(&Deposit {
+            nonce: self.deposits.next_nonce(),
+            token,
+            token_id,
+            amount: amount.map(|amount| amount.0),
+            sender,
+            receiver,
+            chain_to,
+            bundle_data,
+            bundle_salt,
+        })
+    }
+}
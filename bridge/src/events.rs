@@ -1,4 +1,9 @@
 use serde_derive::{Deserialize, Serialize};
+use crate::events_bundle::BundleExecutionData;
+use crate::events_guardian::GuardianActionData;
+use crate::events_migration::{AssetsMigratedData, MigrationData};
+use crate::events_queue::WithdrawalQueueData;
+use crate::events_rescue::TokensRescuedData;
 use crate::events_deposit::{FtDepositedData, NativeDepositedData, NftDepositedData};
 use crate::events_withdraw::{FtWithdrawnData, NativeWithdrawnData, NftWithdrawnData};
 
@@ -10,6 +15,7 @@ pub enum NearEvent<'a> {
     Nep171(Nep171Event<'a>),
     Nep141(Nep141Event<'a>),
     Native(NativeEvent<'a>),
+    Bridge(BridgeEvent<'a>),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -36,6 +42,14 @@ pub struct NativeEvent<'a> {
     pub event_kind: NativeEventKind<'a>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BridgeEvent<'a> {
+    pub version: &'static str,
+    #[serde(flatten)]
+    #[serde(borrow)]
+    pub event_kind: BridgeEventKind<'a>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "event", content = "data")]
 #[serde(rename_all = "snake_case")]
@@ -44,6 +58,7 @@ pub enum Nep171EventKind<'a> {
     #[serde(borrow)]
     NftDeposited(Vec<NftDepositedData<'a>>),
     NftWithdrawn(Vec<NftWithdrawnData<'a>>),
+    NftWithdrawalFailed(Vec<NftWithdrawnData<'a>>),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -54,6 +69,7 @@ pub enum Nep141EventKind<'a> {
     #[serde(borrow)]
     FtDeposited(Vec<FtDepositedData<'a>>),
     FtWithdrawn(Vec<FtWithdrawnData<'a>>),
+    FtWithdrawalFailed(Vec<FtWithdrawnData<'a>>),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -64,6 +80,25 @@ pub enum NativeEventKind<'a> {
     #[serde(borrow)]
     NativeDeposited(Vec<NativeDepositedData<'a>>),
     NativeWithdrawn(Vec<NativeWithdrawnData<'a>>),
+    NativeWithdrawalFailed(Vec<NativeWithdrawnData<'a>>),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
+pub enum BridgeEventKind<'a> {
+    #[serde(borrow)]
+    WithdrawalQueued(Vec<WithdrawalQueueData<'a>>),
+    WithdrawalClaimed(Vec<WithdrawalQueueData<'a>>),
+    WithdrawalCancelled(Vec<WithdrawalQueueData<'a>>),
+    GuardianAction(Vec<GuardianActionData<'a>>),
+    BundleExecuted(Vec<BundleExecutionData<'a>>),
+    BundleFailed(Vec<BundleExecutionData<'a>>),
+    MigrationAnnounced(Vec<MigrationData<'a>>),
+    MigrationCancelled(Vec<MigrationData<'a>>),
+    AssetsMigrated(Vec<AssetsMigratedData<'a>>),
+    TokensRescued(Vec<TokensRescuedData<'a>>),
 }
 
 impl<'a> NearEvent<'a> {
@@ -88,6 +123,13 @@ impl<'a> NearEvent<'a> {
         })
     }
 
+    pub fn new_bridge(version: &'static str, event_kind: BridgeEventKind<'a>) -> Self {
+        NearEvent::Bridge(BridgeEvent {
+            version,
+            event_kind,
+        })
+    }
+
     pub fn new_171_v1(event_kind: Nep171EventKind<'a>) -> Self {
         NearEvent::new_171("1.0.0", event_kind)
     }
@@ -100,6 +142,10 @@ impl<'a> NearEvent<'a> {
         NearEvent::new_native("1.0.0", event_kind)
     }
 
+    pub fn new_bridge_v1(event_kind: BridgeEventKind<'a>) -> Self {
+        NearEvent::new_bridge("1.0.0", event_kind)
+    }
+
     #[must_use = "don't forget to .emit() the event"]
     pub fn nft_deposited(data: Vec<NftDepositedData<'a>>) -> Self {
         NearEvent::new_171_v1(Nep171EventKind::NftDeposited(data))
@@ -110,6 +156,11 @@ impl<'a> NearEvent<'a> {
         NearEvent::new_171_v1(Nep171EventKind::NftWithdrawn(data))
     }
 
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn nft_withdrawal_failed(data: Vec<NftWithdrawnData<'a>>) -> Self {
+        NearEvent::new_171_v1(Nep171EventKind::NftWithdrawalFailed(data))
+    }
+
     #[must_use = "don't forget to .emit() the event"]
     pub fn ft_deposited(data: Vec<FtDepositedData<'a>>) -> Self {
         NearEvent::new_141_v1(Nep141EventKind::FtDeposited(data))
@@ -120,6 +171,11 @@ impl<'a> NearEvent<'a> {
         NearEvent::new_141_v1(Nep141EventKind::FtWithdrawn(data))
     }
 
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn ft_withdrawal_failed(data: Vec<FtWithdrawnData<'a>>) -> Self {
+        NearEvent::new_141_v1(Nep141EventKind::FtWithdrawalFailed(data))
+    }
+
     #[must_use = "don't forget to .emit() the event"]
     pub fn native_deposited(data: Vec<NativeDepositedData<'a>>) -> Self {
         NearEvent::new_native_v1(NativeEventKind::NativeDeposited(data))
@@ -130,6 +186,61 @@ impl<'a> NearEvent<'a> {
         NearEvent::new_native_v1(NativeEventKind::NativeWithdrawn(data))
     }
 
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn native_withdrawal_failed(data: Vec<NativeWithdrawnData<'a>>) -> Self {
+        NearEvent::new_native_v1(NativeEventKind::NativeWithdrawalFailed(data))
+    }
+
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn withdrawal_queued(data: Vec<WithdrawalQueueData<'a>>) -> Self {
+        NearEvent::new_bridge_v1(BridgeEventKind::WithdrawalQueued(data))
+    }
+
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn withdrawal_claimed(data: Vec<WithdrawalQueueData<'a>>) -> Self {
+        NearEvent::new_bridge_v1(BridgeEventKind::WithdrawalClaimed(data))
+    }
+
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn withdrawal_cancelled(data: Vec<WithdrawalQueueData<'a>>) -> Self {
+        NearEvent::new_bridge_v1(BridgeEventKind::WithdrawalCancelled(data))
+    }
+
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn guardian_action(data: Vec<GuardianActionData<'a>>) -> Self {
+        NearEvent::new_bridge_v1(BridgeEventKind::GuardianAction(data))
+    }
+
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn bundle_executed(data: Vec<BundleExecutionData<'a>>) -> Self {
+        NearEvent::new_bridge_v1(BridgeEventKind::BundleExecuted(data))
+    }
+
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn bundle_failed(data: Vec<BundleExecutionData<'a>>) -> Self {
+        NearEvent::new_bridge_v1(BridgeEventKind::BundleFailed(data))
+    }
+
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn migration_announced(data: Vec<MigrationData<'a>>) -> Self {
+        NearEvent::new_bridge_v1(BridgeEventKind::MigrationAnnounced(data))
+    }
+
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn migration_cancelled(data: Vec<MigrationData<'a>>) -> Self {
+        NearEvent::new_bridge_v1(BridgeEventKind::MigrationCancelled(data))
+    }
+
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn assets_migrated(data: Vec<AssetsMigratedData<'a>>) -> Self {
+        NearEvent::new_bridge_v1(BridgeEventKind::AssetsMigrated(data))
+    }
+
+    #[must_use = "don't forget to .emit() the event"]
+    pub fn tokens_rescued(data: Vec<TokensRescuedData<'a>>) -> Self {
+        NearEvent::new_bridge_v1(BridgeEventKind::TokensRescued(data))
+    }
+
     pub(crate) fn to_json_string(&self) -> String {
         near_sdk::serde_json::to_string(self).unwrap()
     }
@@ -0,0 +1,22 @@
+use near_sdk::AccountId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BundleExecutionData<'a> {
+    #[serde(borrow)]
+    pub origin: &'a str,
+    #[serde(borrow)]
+    pub proxy: &'a str,
+    #[serde(borrow)]
+    pub receiver: &'a str,
+}
+
+impl<'a> BundleExecutionData<'a> {
+    pub fn new(origin: &'a str, proxy: &'a AccountId, receiver: &'a AccountId) -> BundleExecutionData<'a> {
+        Self {
+            origin,
+            proxy: proxy.as_str(),
+            receiver: receiver.as_str(),
+        }
+    }
+}
@@ -20,6 +20,7 @@ pub struct NftDepositedData<'a> {
     pub is_wrapped: bool,
     pub bundle_data: Option<String>,
     pub bundle_salt: Option<String>,
+    pub deposit_nonce: u64,
 }
 
 impl<'a> NftDepositedData<'a> {
@@ -32,6 +33,7 @@ impl<'a> NftDepositedData<'a> {
         is_wrapped: bool,
         bundle_data: Option<String>,
         bundle_salt: Option<String>,
+        deposit_nonce: u64,
     ) -> NftDepositedData<'a> {
         let data = Self {
             token: token.as_str(),
@@ -42,6 +44,7 @@ impl<'a> NftDepositedData<'a> {
             is_wrapped,
             bundle_data,
             bundle_salt,
+            deposit_nonce,
         };
         data
     }
@@ -62,6 +65,7 @@ pub struct FtDepositedData<'a> {
     pub is_wrapped: bool,
     pub bundle_data: Option<String>,
     pub bundle_salt: Option<String>,
+    pub deposit_nonce: u64,
 }
 
 impl<'a> FtDepositedData<'a> {
@@ -74,6 +78,7 @@ impl<'a> FtDepositedData<'a> {
         is_wrapped: bool,
         bundle_data: Option<String>,
         bundle_salt: Option<String>,
+        deposit_nonce: u64,
     ) -> FtDepositedData<'a> {
         Self {
             token: token.as_str(),
@@ -84,6 +89,7 @@ impl<'a> FtDepositedData<'a> {
             is_wrapped,
             bundle_data,
             bundle_salt,
+            deposit_nonce,
         }
     }
 }
@@ -101,6 +107,7 @@ pub struct NativeDepositedData<'a> {
     pub amount: &'a str,
     pub bundle_data: Option<String>,
     pub bundle_salt: Option<String>,
+    pub deposit_nonce: u64,
 }
 
 impl<'a> NativeDepositedData<'a> {
@@ -111,6 +118,7 @@ impl<'a> NativeDepositedData<'a> {
         chain_to: &'a str,
         bundle_data: Option<String>,
         bundle_salt: Option<String>,
+        deposit_nonce: u64,
     ) -> NativeDepositedData<'a> {
         Self {
             sender: sender.as_str(),
@@ -119,6 +127,7 @@ impl<'a> NativeDepositedData<'a> {
             chain_to,
             bundle_data,
             bundle_salt,
+            deposit_nonce,
         }
     }
 }
@@ -0,0 +1,29 @@
+use near_sdk::AccountId;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GuardianActionData<'a> {
+    #[serde(borrow)]
+    pub guardian: &'a str,
+    #[serde(borrow)]
+    pub action: &'a str,
+    // Origin of the cancelled withdrawal
+    #[serde(borrow)]
+    pub origin: Option<&'a str>,
+    // JSON of the paused scope
+    #[serde(borrow)]
+    pub scope: Option<&'a str>,
+}
+
+impl<'a> GuardianActionData<'a> {
+    pub fn new(guardian: &'a AccountId, action: &'a str, origin: Option<&'a str>, scope: Option<&'a str>) -> GuardianActionData<'a> {
+        Self {
+            guardian: guardian.as_str(),
+            action,
+            origin,
+            scope,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use near_sdk::AccountId;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MigrationData<'a> {
+    #[serde(borrow)]
+    pub successor: &'a str,
+    #[serde(borrow)]
+    pub executable_at: Option<&'a str>,
+}
+
+impl<'a> MigrationData<'a> {
+    pub fn new(successor: &'a AccountId, executable_at: Option<&'a str>) -> MigrationData<'a> {
+        Self {
+            successor: successor.as_str(),
+            executable_at,
+        }
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AssetsMigratedData<'a> {
+    #[serde(borrow)]
+    pub successor: &'a str,
+    // Empty for native NEAR
+    #[serde(borrow)]
+    pub token: Option<&'a str>,
+    #[serde(borrow)]
+    pub token_id: Option<&'a str>,
+    #[serde(borrow)]
+    pub amount: Option<&'a str>,
+}
+
+impl<'a> AssetsMigratedData<'a> {
+    pub fn new(
+        successor: &'a AccountId,
+        token: Option<&'a str>,
+        token_id: Option<&'a str>,
+        amount: Option<&'a str>,
+    ) -> AssetsMigratedData<'a> {
+        Self {
+            successor: successor.as_str(),
+            token,
+            token_id,
+            amount,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use near_sdk::AccountId;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WithdrawalQueueData<'a> {
+    #[serde(borrow)]
+    pub origin: &'a str,
+    #[serde(borrow)]
+    pub receiver: &'a str,
+    #[serde(borrow)]
+    pub token: Option<&'a str>,
+    #[serde(borrow)]
+    pub token_id: Option<&'a str>,
+    #[serde(borrow)]
+    pub amount: Option<&'a str>,
+    #[serde(borrow)]
+    pub release_at: &'a str,
+    // Account that claimed or cancelled the withdrawal
+    #[serde(borrow)]
+    pub sender: Option<&'a str>,
+}
+
+impl<'a> WithdrawalQueueData<'a> {
+    pub fn new(
+        origin: &'a str,
+        receiver: &'a AccountId,
+        token: Option<&'a str>,
+        token_id: Option<&'a str>,
+        amount: Option<&'a str>,
+        release_at: &'a str,
+        sender: Option<&'a AccountId>,
+    ) -> WithdrawalQueueData<'a> {
+        Self {
+            origin,
+            receiver: receiver.as_str(),
+            token,
+            token_id,
+            amount,
+            release_at,
+            sender: sender.map(|sender| sender.as_str()),
+        }
+    }
+}
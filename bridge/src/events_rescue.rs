@@ -0,0 +1,32 @@
+use near_sdk::AccountId;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TokensRescuedData<'a> {
+    #[serde(borrow)]
+    pub token: &'a str,
+    #[serde(borrow)]
+    pub token_id: Option<&'a str>,
+    #[serde(borrow)]
+    pub amount: Option<&'a str>,
+    #[serde(borrow)]
+    pub receiver: &'a str,
+}
+
+impl<'a> TokensRescuedData<'a> {
+    pub fn new(
+        token: &'a AccountId,
+        token_id: Option<&'a str>,
+        amount: Option<&'a str>,
+        receiver: &'a AccountId,
+    ) -> TokensRescuedData<'a> {
+        Self {
+            token: token.as_str(),
+            token_id,
+            amount,
+            receiver: receiver.as_str(),
+        }
+    }
+}
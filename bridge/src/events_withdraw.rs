@@ -2,7 +2,6 @@ use near_contract_standards::non_fungible_token::TokenId;
 use near_sdk::AccountId;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
-use shared::{Hash, RecoveryID};
 
 #[skip_serializing_none]
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -18,9 +17,7 @@ pub struct NftWithdrawnData<'a> {
     #[serde(borrow)]
     pub origin: &'a str,
     #[serde(borrow)]
-    pub signature: &'a str,
-    pub path: Vec<String>,
-    pub recovery_id: RecoveryID,
+    pub root: &'a str,
     pub is_wrapped: bool,
 }
 
@@ -31,9 +28,7 @@ impl<'a> NftWithdrawnData<'a> {
         sender: &'a AccountId,
         receiver: &'a AccountId,
         origin: &'a str,
-        signature: &'a str,
-        path: Vec<Hash>,
-        recovery_id: RecoveryID,
+        root: &'a str,
         is_wrapped: bool,
     ) -> NftWithdrawnData<'a> {
         Self {
@@ -42,9 +37,7 @@ impl<'a> NftWithdrawnData<'a> {
             sender: sender.as_str(),
             receiver: receiver.as_str(),
             origin,
-            signature,
-            path: path_to_str_vec(path),
-            recovery_id,
+            root,
             is_wrapped,
         }
     }
@@ -62,11 +55,9 @@ pub struct FtWithdrawnData<'a> {
     #[serde(borrow)]
     pub origin: &'a str,
     #[serde(borrow)]
-    pub signature: &'a str,
-    #[serde(borrow)]
     pub amount: &'a str,
-    pub path: Vec<String>,
-    pub recovery_id: RecoveryID,
+    #[serde(borrow)]
+    pub root: &'a str,
     pub is_wrapped: bool,
 }
 
@@ -77,9 +68,7 @@ impl<'a> FtWithdrawnData<'a> {
         sender: &'a AccountId,
         receiver: &'a AccountId,
         origin: &'a str,
-        signature: &'a str,
-        path: Vec<Hash>,
-        recovery_id: RecoveryID,
+        root: &'a str,
         is_wrapped: bool,
     ) -> FtWithdrawnData<'a> {
         Self {
@@ -88,9 +77,7 @@ impl<'a> FtWithdrawnData<'a> {
             receiver: receiver.as_str(),
             amount,
             origin,
-            signature,
-            path: path_to_str_vec(path),
-            recovery_id,
+            root,
             is_wrapped,
         }
     }
@@ -108,9 +95,7 @@ pub struct NativeWithdrawnData<'a> {
     #[serde(borrow)]
     pub origin: &'a str,
     #[serde(borrow)]
-    pub signature: &'a str,
-    pub path: Vec<String>,
-    pub recovery_id: RecoveryID,
+    pub root: &'a str,
 }
 
 impl<'a> NativeWithdrawnData<'a> {
@@ -119,22 +104,14 @@ impl<'a> NativeWithdrawnData<'a> {
         sender: &'a AccountId,
         receiver: &'a AccountId,
         origin: &'a str,
-        signature: &'a str,
-        path: Vec<Hash>,
-        recovery_id: RecoveryID,
+        root: &'a str,
     ) -> NativeWithdrawnData<'a> {
         Self {
             sender: sender.as_str(),
             receiver: receiver.as_str(),
             amount,
             origin,
-            signature,
-            path: path_to_str_vec(path),
-            recovery_id,
+            root,
         }
     }
 }
-
-fn path_to_str_vec<'a>(path: Vec<Hash>) -> Vec<String> {
-    path.iter().map(|h| h.as_str()).collect()
-}
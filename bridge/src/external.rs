@@ -1,10 +1,15 @@
+use std::collections::HashMap;
+
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 use near_contract_standards::non_fungible_token::{Token, TokenId};
-use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
+use near_contract_standards::non_fungible_token::metadata::{NFTContractMetadata, TokenMetadata};
+use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds};
 use near_sdk::AccountId;
 use near_sdk::ext_contract;
 use near_sdk::json_types::U128;
 
+use shared::{AddressFormat, BundleCall};
+
 // Validator interfaces, for cross-contract calls
 
 #[ext_contract(ext_non_fungible_token)]
@@ -16,22 +21,63 @@ trait NonFungibleToken {
         approval_id: Option<u64>,
         memo: Option<String>,
     );
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> bool;
     fn nft_mint(
         &mut self,
         token_id: TokenId,
         receiver_id: AccountId,
         token_metadata: TokenMetadata,
-        memo: Option<String>,
+        refund_id: Option<AccountId>,
+        royalty: Option<HashMap<AccountId, u32>>,
+    );
+    fn nft_burn(&mut self, token_id: TokenId);
+    fn nft_update_metadata(&mut self, metadata: NFTContractMetadata);
+    fn nft_update_token_metadata(
+        &mut self,
+        token_id: TokenId,
+        token_metadata: TokenMetadata,
+        refund_id: Option<AccountId>,
+        royalty: Option<HashMap<AccountId, u32>>,
     );
     fn nft_token(
         &self,
         token_id: TokenId,
     ) -> Option<Token>;
+    fn nft_tokens_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token>;
 }
 
 #[ext_contract(ext_fungible_token)]
 trait FungibleToken {
     fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
-    fn ft_mint(&mut self, receiver_id: AccountId, amount: U128);
+    fn ft_transfer_call(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>, msg: String) -> U128;
+    fn ft_mint(&mut self, receiver_id: AccountId, amount: U128, refund_id: Option<AccountId>);
+    fn ft_burn(&mut self, amount: U128);
     fn ft_metadata(&self) -> FungibleTokenMetadata;
+    fn ft_balance_of(&self, account_id: AccountId) -> U128;
+    fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>) -> StorageBalance;
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance>;
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds;
+}
+
+#[ext_contract(ext_feer)]
+trait Feer {
+    fn set_paused(&mut self, paused: bool);
+    fn set_chain(&mut self, chain: String, format: Option<AddressFormat>);
+}
+
+#[ext_contract(ext_bundle_proxy)]
+trait BundleProxy {
+    fn execute(&mut self, calls: Vec<BundleCall>) -> bool;
 }
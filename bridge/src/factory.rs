@@ -0,0 +1,270 @@
+use std::str::FromStr;
+
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_contract_standards::non_fungible_token::metadata::NFTContractMetadata;
+use near_sdk::{AccountId, Balance, env, near_bindgen, Promise, require};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LazyOption, LookupMap};
+use near_sdk::json_types::U128;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use shared::{ACCOUNT_STORAGE_BYTES, AuthorizedOperation, CALL_GAS, GAS_FOR_TX, OperationType, RecoverableSignature, TokenType, TRANSFER_DEPOSIT};
+
+use super::*;
+
+/// State written by `new` of the token contracts with empty metadata and a 64 characters owner, the
+/// default icon included. Measured with the unit test environment of the token contracts.
+const WRAPPED_FT_STATE_BYTES: u64 = 2612;
+const WRAPPED_NFT_STATE_BYTES: u64 = 2510;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct TokenFactory {
+    /// Wasm blobs of the `fungible-token` and `non-fungible-token` contracts.
+    pub codes: LookupMap<TokenType, Vec<u8>>,
+    /// Wasm blob of the `bundle-proxy` contract.
+    pub proxy_code: LazyOption<Vec<u8>>,
+}
+
+impl TokenFactory {
+    pub fn new() -> Self {
+        Self {
+            codes: LookupMap::new(b"token_codes".to_vec()),
+            proxy_code: LazyOption::new(b"proxy_code".to_vec(), None),
+        }
+    }
+
+    pub fn set_code(&mut self, token_type: TokenType, code: Vec<u8>) {
+        assert!(token_type != TokenType::Native, "Factory: native token has no contract");
+        self.codes.insert(&token_type, &code);
+    }
+
+    pub fn get_code(&self, token_type: TokenType) -> Vec<u8> {
+        self.codes.get(&token_type).unwrap_or_else(|| env::panic_str("Factory: contract code is not stored"))
+    }
+
+    pub fn get_proxy_code(&self) -> Vec<u8> {
+        self.proxy_code.get().unwrap_or_else(|| env::panic_str("Factory: proxy code is not stored"))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct TokenCodeArgs {
+    token_type: TokenType,
+    code: String,
+    signatures: Vec<RecoverableSignature>,
+}
+
+#[near_bindgen]
+impl Bridge {
+    /// Stores the contract code used to deploy wrapped tokens of the given type.
+    pub fn store_token_code(&mut self) {
+        // Receive the arguments directly from the input to avoid the
+        // GAS overhead of deserializing parameters
+        let input = env::input().expect("Error: No input").to_vec();
+        let args: TokenCodeArgs = serde_json::from_slice(&input).unwrap();
+        let code = base64::decode(&args.code).expect("Error: Invalid base64 string").to_vec();
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::StoreTokenCode)
+                .borsh(&args.token_type)
+                .bytes(env::keccak256(code.as_slice()).as_slice())
+        );
+
+        self.check_signature_and_increment_nonce(hash, args.signatures);
+        self.factory.set_code(args.token_type, code);
+    }
+
+    /// Stores the contract code used to deploy bundle proxies.
+    pub fn store_proxy_code(&mut self) {
+        // Receive the arguments directly from the input to avoid the
+        // GAS overhead of deserializing parameters
+        let input = env::input().expect("Error: No input").to_vec();
+        let args: ContractUpdateArgs = serde_json::from_slice(&input).unwrap();
+        let code = base64::decode(&args.code).expect("Error: Invalid base64 string").to_vec();
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::StoreProxyCode).bytes(env::keccak256(code.as_slice()).as_slice())
+        );
+
+        self.check_signature_and_increment_nonce(hash, args.signatures);
+        self.factory.proxy_code.set(&code);
+    }
+
+    /// Deploys the `fungible-token` contract to the `<symbol>.<bridge>` account owned by the bridge
+    /// and registers it as a wrapped token. The attached deposit pays for the account storage.
+    #[payable]
+    pub fn deploy_wrapped_ft(
+        &mut self,
+        metadata: FungibleTokenMetadata,
+        chain: String,
+        signatures: Vec<RecoverableSignature>,
+    ) -> Promise {
+        metadata.assert_valid();
+
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::DeployWrappedFT).borsh(&metadata).string(&chain)
+        );
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        let args = json!({
+            "owner_id": env::current_account_id(),
+            "total_supply": U128(0),
+            "metadata": metadata,
+        });
+
+        self.internal_deploy_wrapped_token(
+            TokenType::FT,
+            metadata.symbol.clone(),
+            args.to_string().into_bytes(),
+            WRAPPED_FT_STATE_BYTES + metadata.try_to_vec().unwrap().len() as u64,
+            chain,
+            metadata.decimals,
+        )
+    }
+
+    /// Deploys the `non-fungible-token` contract to the `<symbol>.<bridge>` account owned by the
+    /// bridge and registers it as a wrapped token. The attached deposit pays for the account storage.
+    #[payable]
+    pub fn deploy_wrapped_nft(
+        &mut self,
+        metadata: NFTContractMetadata,
+        chain: String,
+        signatures: Vec<RecoverableSignature>,
+    ) -> Promise {
+        metadata.assert_valid();
+
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::DeployWrappedNFT).borsh(&metadata).string(&chain)
+        );
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        let args = json!({
+            "owner_id": env::current_account_id(),
+            "metadata": metadata,
+        });
+
+        self.internal_deploy_wrapped_token(
+            TokenType::NFT,
+            metadata.symbol.clone(),
+            args.to_string().into_bytes(),
+            WRAPPED_NFT_STATE_BYTES + metadata.try_to_vec().unwrap().len() as u64,
+            chain,
+            0,
+        )
+    }
+
+    /// Replaces the contract metadata of the wrapped NFT, for example its `base_uri` or `icon`.
+    pub fn update_wrapped_nft_metadata(
+        &mut self,
+        token: AccountId,
+        metadata: NFTContractMetadata,
+        signatures: Vec<RecoverableSignature>,
+    ) -> Promise {
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::UpdateWrappedNFTMetadata).account(&token).borsh(&metadata)
+        );
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        let info = self.tokens.get_registered_token(&token, TokenType::NFT);
+        require!(info.is_wrapped(), "Factory: token is not wrapped");
+
+        ext_non_fungible_token::ext(token)
+            .with_static_gas(GAS_FOR_TX)
+            .with_attached_deposit(TRANSFER_DEPOSIT)
+            .nft_update_metadata(metadata)
+    }
+
+    /// Applies the metadata changed on the origin chain to an existing wrapped NFT. The attached
+    /// deposit pays for the additional storage, the surplus is refunded to the caller. The whole
+    /// deposit is refunded if the update fails.
+    #[payable]
+    pub fn update_wrapped_nft_token_metadata(
+        &mut self,
+        token: AccountId,
+        token_id: TokenId,
+        token_metadata: NftMetadata,
+        signatures: Vec<RecoverableSignature>,
+    ) -> Promise {
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::UpdateWrappedNFTTokenMetadata)
+                .account(&token)
+                .string(&token_id)
+                .borsh(&token_metadata)
+        );
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        let info = self.tokens.get_registered_token(&token, TokenType::NFT);
+        require!(info.is_wrapped(), "Factory: token is not wrapped");
+
+        ext_non_fungible_token::ext(token)
+            .with_static_gas(GAS_FOR_TX)
+            .with_attached_deposit(env::attached_deposit())
+            .nft_update_token_metadata(token_id, token_metadata.to_token_metadata(), Some(env::predecessor_account_id()), token_metadata.royalty)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TX)
+                    .update_wrapped_nft_token_metadata_callback(env::predecessor_account_id(), U128(env::attached_deposit()))
+            )
+    }
+
+    #[private]
+    pub fn deploy_wrapped_token_callback(&mut self, info: TokenInfo, sender: AccountId, deposit: U128) {
+        if is_promise_succeeded(0) {
+            self.tokens.add_token(info);
+        } else {
+            // The failed receipt returns the deposit to the bridge, pass it back to the caller
+            Promise::new(sender).transfer(deposit.0);
+        }
+    }
+
+    #[private]
+    pub fn update_wrapped_nft_token_metadata_callback(&mut self, sender: AccountId, deposit: U128) {
+        if !is_promise_succeeded(0) && deposit.0 > 0 {
+            // The failed receipt returns the deposit to the bridge, pass it back to the caller
+            Promise::new(sender).transfer(deposit.0);
+        }
+    }
+}
+
+impl Bridge {
+    /// `state_bytes` is the storage the `new` call of the token contract writes.
+    fn internal_deploy_wrapped_token(
+        &mut self,
+        token_type: TokenType,
+        symbol: String,
+        init_args: Vec<u8>,
+        state_bytes: u64,
+        chain: String,
+        decimals: u8,
+    ) -> Promise {
+        let token = AccountId::from_str(format!("{}.{}", symbol.to_lowercase(), env::current_account_id()).as_str())
+            .unwrap_or_else(|_| env::panic_str("Factory: symbol is not a valid account name"));
+        require!(self.tokens.get_token(&token).is_none(), "Factory: token already registered");
+
+        let code = self.factory.get_code(token_type.clone());
+        let deposit = env::attached_deposit();
+        let min_deposit = Balance::from(code.len() as u64 + state_bytes + ACCOUNT_STORAGE_BYTES) * env::storage_byte_cost();
+        require!(
+            deposit >= min_deposit,
+            format!("Requires attached deposit of at least {} yoctoNEAR", min_deposit),
+        );
+
+        let info = TokenInfo {
+            token: token.clone(),
+            token_type,
+            mode: TokenMode::Wrapped,
+            chain,
+            decimals,
+        };
+
+        Promise::new(token)
+            .create_account()
+            .transfer(deposit)
+            .deploy_contract(code)
+            .function_call("new".to_string(), init_args, 0, CALL_GAS)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TX)
+                    .deploy_wrapped_token_callback(info, env::predecessor_account_id(), U128(deposit))
+            )
+    }
+}
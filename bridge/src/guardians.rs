@@ -0,0 +1,135 @@
+use near_contract_tools::pause::Pause;
+use near_sdk::{AccountId, env, near_bindgen, Promise};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{UnorderedSet, Vector};
+use near_sdk::json_types::U64;
+use serde::{Deserialize, Serialize};
+
+use shared::{AuthorizedOperation, GAS_FOR_TX, OperationType, RecoverableSignature};
+
+use crate::events_guardian::GuardianActionData;
+use crate::pause_scopes::PauseScope;
+
+use super::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub enum GuardianActionKind {
+    Pause,
+    CancelWithdrawal {
+        origin: String,
+    },
+    PauseScope {
+        scope: PauseScope,
+    },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub struct GuardianAction {
+    pub guardian: AccountId,
+    pub action: GuardianActionKind,
+    pub timestamp: U64,
+}
+
+/// Accounts allowed to take emergency actions without the signers.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Guardians {
+    pub guardians: UnorderedSet<AccountId>,
+    pub actions: Vector<GuardianAction>,
+}
+
+impl Guardians {
+    pub fn new() -> Self {
+        Self {
+            guardians: UnorderedSet::new(b"guardians".to_vec()),
+            actions: Vector::new(b"guardian_actions".to_vec()),
+        }
+    }
+
+    pub fn add_guardian(&mut self, guardian: &AccountId) {
+        assert!(self.guardians.insert(guardian), "Guardians: guardian already exists");
+    }
+
+    pub fn remove_guardian(&mut self, guardian: &AccountId) {
+        assert!(self.guardians.remove(guardian), "Guardians: guardian does not exist");
+    }
+
+    pub fn assert_guardian(&self, account: &AccountId) {
+        if !self.guardians.contains(account) {
+            env::panic_str("Guardians: caller is not a guardian");
+        }
+    }
+
+    pub fn get_guardians(&self) -> Vec<AccountId> {
+        self.guardians.to_vec()
+    }
+
+    /// Checks that the caller is a guardian, stores the action and emits the guardian action event.
+    pub fn record_action(&mut self, guardian: &AccountId, action: GuardianActionKind) {
+        self.assert_guardian(guardian);
+
+        let (name, origin, scope) = match &action {
+            GuardianActionKind::Pause => ("pause", None, None),
+            GuardianActionKind::CancelWithdrawal { origin } => ("cancel_withdrawal", Some(origin.clone()), None),
+            GuardianActionKind::PauseScope { scope } => ("pause_scope", None, Some(serde_json::to_string(scope).unwrap())),
+        };
+        NearEvent::guardian_action(vec![GuardianActionData::new(guardian, name, origin.as_deref(), scope.as_deref())]).emit();
+
+        self.actions.push(&GuardianAction {
+            guardian: guardian.clone(),
+            action,
+            timestamp: U64(env::block_timestamp()),
+        });
+    }
+
+    pub fn get_actions(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<GuardianAction> {
+        self.actions.iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u64::MAX) as usize)
+            .collect()
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    pub fn get_guardians(&self) -> Vec<AccountId> {
+        self.guardians.get_guardians()
+    }
+
+    pub fn get_guardian_actions(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<GuardianAction> {
+        self.guardians.get_actions(from_index, limit)
+    }
+
+    /// Pauses the bridge and the deposits on the fee contract without the signers, can be called
+    /// only by guardians. Resuming requires the signed `resume_bridge`. The fee contract is paused
+    /// even if the bridge already is, so a missed pause can be repeated.
+    pub fn pause(&mut self) -> Promise {
+        self.guardians.record_action(&env::predecessor_account_id(), GuardianActionKind::Pause);
+
+        if !Self::is_paused() {
+            <Bridge as Pause>::pause(self);
+        }
+        self.internal_set_feer_paused(true)
+    }
+
+    pub fn add_guardian(&mut self, guardian: AccountId, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::AddGuardian).account(&guardian));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.guardians.add_guardian(&guardian);
+    }
+
+    pub fn remove_guardian(&mut self, guardian: AccountId, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::RemoveGuardian).account(&guardian));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.guardians.remove_guardian(&guardian);
+    }
+}
+
+impl Bridge {
+    pub(crate) fn internal_set_feer_paused(&self, paused: bool) -> Promise {
+        ext_feer::ext(self.fee_contract.clone())
+            .with_static_gas(GAS_FOR_TX)
+            .set_paused(paused)
+    }
+}
@@ -3,34 +3,95 @@ extern crate core;
 use std::str::FromStr;
 
 use near_contract_standards::non_fungible_token::{Token, TokenId};
-use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
 use near_contract_tools::{Pause, pause::Pause};
 use near_contract_tools::pause::PauseExternal;
-use near_sdk::{AccountId, assert_one_yocto, Balance, env, near_bindgen, PanicOnDefault, Promise, PromiseError, PromiseOrValue, require};
+use near_sdk::{AccountId, assert_one_yocto, Balance, env, Gas, near_bindgen, PanicOnDefault, Promise, PromiseError, PromiseOrValue, require};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::json_types::U128;
+use near_sdk::json_types::{U128, U64};
 use serde::{Deserialize, Serialize};
 use events::NearEvent;
 
-use shared::{CALL_GAS, ContentNode, Data, FT_MINT_STORAGE_DEPOSIT, GAS_FOR_TX, get_merkle_root, Hash, Hashes, NFT_MINT_STORAGE_DEPOSIT, NO_ARGS, RecoveryID, Secp256K1Signature, SignerPublicKey, TokenType, u128_to_bytes, verify_ecdsa_signature};
+use shared::{AuthorizedOperation, Bundle, CALL_GAS, Chains, GAS_FOR_TRANSFER_CALL, GAS_FOR_TX, Hash, Hashes, NFT_MINT_STORAGE_DEPOSIT, NO_ARGS, OperationType, RecoverableSignature, SignerPublicKey, SignerSet, TokenType, TRANSFER_DEPOSIT};
 
+use crate::bundles::BundleProxies;
+use crate::deposits::DepositsAccumulator;
 use crate::events_deposit::*;
 use crate::events_withdraw::{FtWithdrawnData, NativeWithdrawnData, NftWithdrawnData};
 use crate::external::*;
+use crate::factory::TokenFactory;
+use crate::guardians::Guardians;
+use crate::limits::LimitsManager;
+use crate::locked::LockedLedger;
+use crate::migration::MigrationManager;
+use crate::pause_scopes::PauseScopes;
+use crate::pending::PendingWithdrawals;
 use crate::merkle::*;
 use crate::nft::*;
-
+use crate::roots::Roots;
+use crate::supply::WrappedSupply;
+use crate::timelock::WithdrawalQueue;
+use crate::tokens::*;
+use crate::withdrawals::*;
+
+mod bundles;
+mod chains;
+mod deposits;
 mod events;
+mod events_bundle;
 mod events_deposit;
+mod events_guardian;
+mod events_migration;
+mod events_queue;
+mod events_rescue;
 mod events_withdraw;
 mod external;
+mod factory;
+mod guardians;
+mod limits;
+mod locked;
+mod migration;
 mod receivers;
 mod merkle;
 mod nft;
+mod pause_scopes;
+mod pending;
+mod rescue;
+mod roots;
+mod storage;
+mod supply;
+mod timelock;
+mod tokens;
+mod withdrawals;
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault, Pause)]
 pub struct Bridge {
+    pub signers: SignerSet,
+    pub hashes: Hashes,
+    pub roots: Roots,
+    pub tokens: TokensManager,
+    pub factory: TokenFactory,
+    pub pending: PendingWithdrawals,
+    pub limits: LimitsManager,
+    pub queue: WithdrawalQueue,
+    pub guardians: Guardians,
+    pub pause_scopes: PauseScopes,
+    pub chains: Chains,
+    pub deposits: DepositsAccumulator,
+    pub bundle_proxies: BundleProxies,
+    pub migration: MigrationManager,
+    pub locked: LockedLedger,
+    pub wrapped_supply: WrappedSupply,
+    // Receives the assets not used by the receiver of a withdrawal with a message
+    pub fallback_receiver: Option<AccountId>,
+    pub chain: String,
+    pub fee_contract: AccountId,
+    pub nonce: u128,
+}
+
+/// Layout of the bridge state before the signer set, read once by `migrate`.
+#[derive(BorshDeserialize)]
+pub struct OldBridge {
     pub signer: SignerPublicKey,
     pub hashes: Hashes,
     pub chain: String,
@@ -38,26 +99,63 @@ pub struct Bridge {
     pub nonce: u128,
 }
 
+impl From<OldBridge> for Bridge {
+    fn from(old: OldBridge) -> Self {
+        Self {
+            signers: SignerSet::new(vec![old.signer], 1),
+            hashes: old.hashes,
+            roots: Roots::new(),
+            tokens: TokensManager::new(),
+            factory: TokenFactory::new(),
+            pending: PendingWithdrawals::new(),
+            limits: LimitsManager::new(),
+            queue: WithdrawalQueue::new(),
+            guardians: Guardians::new(),
+            pause_scopes: PauseScopes::new(),
+            chains: Chains::new(),
+            deposits: DepositsAccumulator::new(),
+            bundle_proxies: BundleProxies::new(),
+            migration: MigrationManager::new(),
+            locked: LockedLedger::unseeded(),
+            wrapped_supply: WrappedSupply::new(),
+            fallback_receiver: None,
+            chain: old.chain,
+            fee_contract: old.fee_contract,
+            nonce: old.nonce,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 struct ContractUpdateArgs {
     code: String,
-    signature: String,
-    recovery_id: RecoveryID,
+    signatures: Vec<RecoverableSignature>,
 }
 
 #[near_bindgen]
 impl Bridge {
     #[init]
-    pub fn new(signer: SignerPublicKey, fee_contract: AccountId, chain: String) -> Self {
+    pub fn new(signers: Vec<SignerPublicKey>, threshold: u32, fee_contract: AccountId, chain: String) -> Self {
         assert_ne!(chain, "", "Chain argument is required");
 
-        if !signer.validate() {
-            env::panic_str("Invalid signer public key");
-        }
-
         Self {
-            signer: signer.clone(),
+            signers: SignerSet::new(signers, threshold),
             hashes: Hashes::new(),
+            roots: Roots::new(),
+            tokens: TokensManager::new(),
+            factory: TokenFactory::new(),
+            pending: PendingWithdrawals::new(),
+            limits: LimitsManager::new(),
+            queue: WithdrawalQueue::new(),
+            guardians: Guardians::new(),
+            pause_scopes: PauseScopes::new(),
+            chains: Chains::new(),
+            deposits: DepositsAccumulator::new(),
+            bundle_proxies: BundleProxies::new(),
+            migration: MigrationManager::new(),
+            locked: LockedLedger::new(),
+            wrapped_supply: WrappedSupply::new(),
+            fallback_receiver: None,
             nonce: 0,
             fee_contract,
             chain,
@@ -72,11 +170,10 @@ impl Bridge {
         self.fee_contract.clone()
     }
 
-    pub fn set_fee_contract(&mut self, fee_contract: AccountId, signature: String, recovery_id: RecoveryID) {
-        let sign = Secp256K1Signature::from_hex(signature);
-        let hash = self.get_authorized_operation_hash(fee_contract.clone().to_string());
+    pub fn set_fee_contract(&mut self, fee_contract: AccountId, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::SetFeeContract).account(&fee_contract));
 
-        self.check_signature_and_increment_nonce(hash, sign.clone(), recovery_id.clone());
+        self.check_signature_and_increment_nonce(hash, signatures);
 
         self.fee_contract = fee_contract;
     }
@@ -87,10 +184,11 @@ impl Bridge {
         let input = env::input().expect("Error: No input").to_vec();
         let args: ContractUpdateArgs = serde_json::from_slice(&input).unwrap();
         let code = base64::decode(&args.code).expect("Error: Invalid base64 string").to_vec();
-        let sign = Secp256K1Signature::from_hex(args.signature);
-        let hash = self.get_update_contract_hash(code.clone());
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::UpdateContract).bytes(env::keccak256(code.as_slice()).as_slice())
+        );
 
-        self.check_signature_and_increment_nonce(hash, sign.clone(), args.recovery_id.clone());
+        self.check_signature_and_increment_nonce(hash, args.signatures);
 
         // Deploy the contract on self
         Promise::new(env::current_account_id())
@@ -104,40 +202,59 @@ impl Bridge {
             .as_return()
     }
 
-    pub fn get_signer(&self) -> SignerPublicKey {
-        self.signer.clone()
+    pub fn submit_root(&mut self, root: String, signatures: Vec<RecoverableSignature>) {
+        let root_hash = Hash::from_hex(root);
+
+        self.check_signature(root_hash.clone(), signatures);
+        self.roots.add_root(root_hash);
     }
 
-    #[payable]
-    pub fn set_signer(&mut self, signer: SignerPublicKey, signature: String, recovery_id: RecoveryID) {
-        let sign = Secp256K1Signature::from_hex(signature);
+    /// Returns the block timestamp the root was submitted at, if any.
+    pub fn get_root(&self, root: String) -> Option<U64> {
+        self.roots.get_root(Hash::from_hex(root)).map(U64::from)
+    }
 
-        if !signer.validate() {
-            env::panic_str("Invalid signer public key");
-        }
+    pub fn get_signers(&self) -> SignerSet {
+        self.signers.clone()
+    }
 
-        let hash = self.get_set_signer_hash(signer.to_string().clone());
+    pub fn add_signer(&mut self, signer: SignerPublicKey, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::AddSigner).string(&signer.to_string()));
 
-        self.check_signature_and_increment_nonce(hash, sign.clone(), recovery_id.clone());
-        self.signer = signer;
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.signers.add_signer(signer);
     }
 
-    pub fn pause_bridge(&mut self, signature: String, recovery_id: RecoveryID) {
-        let sign = Secp256K1Signature::from_hex(signature);
-        let hash = self.get_authorized_operation_hash("pause".to_string());
+    pub fn remove_signer(&mut self, signer: SignerPublicKey, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::RemoveSigner).string(&signer.to_string()));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.signers.remove_signer(signer);
+    }
 
-        self.check_signature_and_increment_nonce(hash, sign.clone(), recovery_id.clone());
+    pub fn set_threshold(&mut self, threshold: u32, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::SetThreshold).number(threshold as u128));
 
-        Bridge::pause(self);
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.signers.set_threshold(threshold);
     }
 
-    pub fn resume_bridge(&mut self, signature: String, recovery_id: RecoveryID) {
-        let sign = Secp256K1Signature::from_hex(signature);
-        let hash = self.get_authorized_operation_hash("resume".to_string());
+    pub fn pause_bridge(&mut self, signatures: Vec<RecoverableSignature>) -> Promise {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::Pause));
 
-        self.check_signature_and_increment_nonce(hash, sign.clone(), recovery_id.clone());
+        self.check_signature_and_increment_nonce(hash, signatures);
 
-        Bridge::unpause(self);
+        <Bridge as Pause>::pause(self);
+        self.internal_set_feer_paused(true)
+    }
+
+    pub fn resume_bridge(&mut self, signatures: Vec<RecoverableSignature>) -> Promise {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::Resume));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        <Bridge as Pause>::unpause(self);
+        self.internal_set_feer_paused(false)
     }
 
     #[payable]
@@ -147,66 +264,51 @@ impl Bridge {
         token_id: TokenId,
         receiver_id: AccountId,
         token_metadata: Option<NftMetadata>,
-        is_wrapped: bool,
         origin: String,
         path: Vec<Hash>,
-        signature: String,
-        recovery_id: RecoveryID,
+        bundle: Option<Bundle>,
+        msg: Option<String>,
     ) -> PromiseOrValue<bool> {
         Bridge::require_unpaused();
 
-        if is_wrapped {
+        if self.tokens.get_registered_token(&token, TokenType::NFT).is_wrapped() {
+            // The storage which is not used by the mint is refunded
             require!(
-                env::attached_deposit() == NFT_MINT_STORAGE_DEPOSIT,
-                "Requires attached deposit of 0.2 NEAR",
+                env::attached_deposit() >= NFT_MINT_STORAGE_DEPOSIT,
+                "Requires attached deposit of at least 0.2 NEAR",
             );
 
-            let _token_metadata = token_metadata.clone().unwrap();
-
-            let metadata = TokenMetadata {
-                title: _token_metadata.clone().title,
-                description: _token_metadata.clone().description,
-                media: _token_metadata.clone().media,
-                media_hash: _token_metadata.clone().media_hash,
-                copies: Some(1),
-                issued_at: None,
-                expires_at: None,
-                starts_at: None,
-                updated_at: None,
-                extra: None,
-                reference: None,
-                reference_hash: None,
-            };
-
             self.internal_nft_withdraw(
                 token.clone(),
                 token_id.clone(),
                 receiver_id.clone(),
-                metadata,
-                is_wrapped.clone(),
+                token_metadata.unwrap(),
                 origin.clone(),
                 path.clone(),
-                signature.clone(),
-                recovery_id.clone(),
+                bundle,
+                msg,
+                env::predecessor_account_id(),
             )
         } else {
             assert_one_yocto();
+            let bundle_gas = bundle.as_ref().map_or(Gas(0), |bundle| bundle.required_gas() + GAS_FOR_TX);
+            let transfer_call_gas = msg.as_ref().map_or(Gas(0), |_| GAS_FOR_TRANSFER_CALL * 2 + GAS_FOR_TX);
             let promise = ext_non_fungible_token::ext(token.clone())
                 .with_static_gas(GAS_FOR_TX)
                 .nft_token(token_id.clone())
                 .then(
                     Self::ext(env::current_account_id())
-                        .with_static_gas(GAS_FOR_TX)
+                        .with_static_gas(GAS_FOR_TX + bundle_gas + transfer_call_gas)
                         .with_attached_deposit(env::attached_deposit())
                         .nft_get_callback(
                             token.clone(),
                             token_id.clone(),
                             receiver_id.clone(),
-                            is_wrapped.clone(),
                             origin.clone(),
                             path.clone(),
-                            signature.clone(),
-                            recovery_id.clone(),
+                            bundle,
+                            msg,
+                            env::predecessor_account_id(),
                         )
                 );
 
@@ -220,87 +322,30 @@ impl Bridge {
         token: AccountId,
         amount: U128,
         receiver_id: AccountId,
-        is_wrapped: bool,
         origin: String,
         path: Vec<Hash>,
-        signature: String,
-        recovery_id: RecoveryID,
+        bundle: Option<Bundle>,
+        msg: Option<String>,
     ) -> PromiseOrValue<U128> {
         Bridge::require_unpaused();
 
-        if is_wrapped {
-            require!(
-                env::attached_deposit() == FT_MINT_STORAGE_DEPOSIT,
-                "Requires attached deposit of 0.00125 NEAR",
-            );
-        } else {
-            assert_one_yocto();
-        }
+        let withdrawal = Withdrawal::new(origin, receiver_id, WithdrawalKind::FT { token, amount, msg }, bundle);
 
-        let sign = Secp256K1Signature::from_hex(signature.clone());
-        let origin_hash = Hash::from_hex(origin.clone());
-        let content = ContentNode::new(
-            origin_hash,
-            env::current_account_id(),
-            self.chain.clone(),
-            TransferFullMetaOperation::new_ft_transfer(token.clone(), amount.clone()).get_data(),
-            Some(receiver_id.clone()),
+        // Covers the storage registration of the receiver as well, the unused part is refunded
+        let deposit = self.internal_withdrawal_deposit(&withdrawal);
+        require!(
+            env::attached_deposit() >= deposit,
+            format!("Requires attached deposit of at least {} yoctoNEAR", deposit),
         );
 
-        self.check_signature(get_merkle_root(content, &path.clone()), sign, recovery_id.clone());
-        self.hashes.check_hash(origin_hash.clone());
-
-        if is_wrapped {
-            let promise = ext_fungible_token::ext(token.clone())
-                .with_static_gas(GAS_FOR_TX)
-                .with_attached_deposit(env::attached_deposit())
-                .ft_mint(receiver_id.clone(), amount.clone())
-                .then(
-                    Self::ext(env::current_account_id())
-                        .with_static_gas(GAS_FOR_TX)
-                        .handle_hash_callback(
-                            origin_hash.clone(),
-                            TokenType::FT,
-                            receiver_id.clone(),
-                            env::predecessor_account_id(),
-                            origin.clone(),
-                            path.clone(),
-                            signature.clone(),
-                            recovery_id.clone(),
-                            Some(token.clone()),
-                            Some(is_wrapped.clone()),
-                            None,
-                            Some(amount.clone().0.to_string()),
-                        )
-                );
-
-            PromiseOrValue::from(promise)
-        } else {
-            let promise = ext_fungible_token::ext(token.clone())
-                .with_static_gas(GAS_FOR_TX)
-                .with_attached_deposit(env::attached_deposit())
-                .ft_transfer(receiver_id.clone(), amount.clone(), None)
-                .then(
-                    Self::ext(env::current_account_id())
-                        .with_static_gas(GAS_FOR_TX)
-                        .handle_hash_callback(
-                            origin_hash.clone(),
-                            TokenType::FT,
-                            receiver_id.clone(),
-                            env::predecessor_account_id(),
-                            origin.clone(),
-                            path.clone(),
-                            signature.clone(),
-                            recovery_id.clone(),
-                            Some(token.clone()),
-                            Some(is_wrapped.clone()),
-                            None,
-                            Some(amount.clone().0.to_string()),
-                        )
-                );
+        let root = self.internal_verify_withdrawal(&withdrawal, &path);
 
-            PromiseOrValue::from(promise)
+        if !self.internal_check_withdrawal_delay(&withdrawal, root.clone(), env::attached_deposit()) {
+            // Nothing is transferred until the queued withdrawal is claimed
+            return PromiseOrValue::Value(U128(0));
         }
+
+        PromiseOrValue::from(self.internal_withdraw(withdrawal, root, env::attached_deposit(), env::predecessor_account_id()))
     }
 
     #[payable]
@@ -320,6 +365,20 @@ impl Bridge {
             env::panic_str("Attached deposit must be greater than zero");
         }
 
+        self.pause_scopes.require_deposit_unpaused(None, chain.clone());
+        let receiver_id = self.internal_normalize_receiver(&chain, &receiver_id);
+        self.locked.lock_native(env::attached_deposit());
+        let deposit_nonce = self.internal_commit_deposit(
+            None,
+            None,
+            Some(U128(env::attached_deposit())),
+            sender.clone(),
+            receiver_id.clone(),
+            chain.clone(),
+            bundle_data.clone(),
+            bundle_salt.clone(),
+        );
+
         NearEvent::native_deposited(vec![NativeDepositedData::new(
             env::attached_deposit().to_string().as_str(),
             &sender.clone(),
@@ -327,6 +386,7 @@ impl Bridge {
             chain.as_str(),
             bundle_data.clone(),
             bundle_salt.clone(),
+            deposit_nonce,
         )]).emit();
     }
 
@@ -337,56 +397,36 @@ impl Bridge {
         amount: String,
         origin: String,
         path: Vec<Hash>,
-        signature: String,
-        recovery_id: RecoveryID,
+        bundle: Option<Bundle>,
     ) {
         Bridge::require_unpaused();
 
         assert_one_yocto();
 
-        let amnt = Balance::from_str(amount.as_str()).unwrap();
-        let sign = Secp256K1Signature::from_hex(signature.clone());
-        let origin_hash = Hash::from_hex(origin.clone());
-
-        let content = ContentNode::new(
-            origin_hash,
-            env::current_account_id(),
-            self.chain.clone(),
-            TransferOperation::new_native_transfer(
-                amnt,
-            ).get_data(),
-            Some(receiver_id.clone()),
-        );
+        let amount = Balance::from_str(amount.as_str()).unwrap();
+        let withdrawal = Withdrawal::new(origin, receiver_id, WithdrawalKind::Native { amount: U128(amount) }, bundle);
+        let root = self.internal_verify_withdrawal(&withdrawal, &path);
 
-        self.check_signature(get_merkle_root(content, &path), sign, recovery_id.clone());
-        self.hashes.check_hash(origin_hash.clone());
+        if !self.internal_check_withdrawal_delay(&withdrawal, root.clone(), env::attached_deposit()) {
+            return;
+        }
 
-        Promise::new(receiver_id.clone())
-            .transfer(amnt)
-            .then(Self::ext(env::current_account_id())
-                .with_static_gas(GAS_FOR_TX)
-                .handle_hash_callback(
-                    origin_hash.clone(),
-                    TokenType::Native,
-                    receiver_id.clone(),
-                    env::predecessor_account_id(),
-                    origin.clone(),
-                    path.clone(),
-                    signature.clone(),
-                    recovery_id.clone(),
-                    None,
-                    None,
-                    None,
-                    Some(amount.clone()),
-                )
-            );
+        self.internal_withdraw(withdrawal, root, env::attached_deposit(), env::predecessor_account_id());
     }
 
+    /// Converts the state left by the previous code. The single signer of the old layout becomes a
+    /// 1-of-1 signer set, the state of the current layout is kept as is.
     #[init(ignore_state)]
     #[private]
     pub fn migrate() -> Self {
-        let contract: Bridge = env::state_read().expect("ERROR, NOT INITIALIZED");
-        contract
+        let state = env::storage_read(b"STATE").expect("ERROR, NOT INITIALIZED");
+
+        if let Ok(contract) = Bridge::try_from_slice(&state) {
+            return contract;
+        }
+
+        let old = OldBridge::try_from_slice(&state).unwrap_or_else(|_| env::panic_str("ERROR, UNKNOWN STATE LAYOUT"));
+        Bridge::from(old)
     }
 
     #[private]
@@ -398,120 +438,49 @@ impl Bridge {
     }
 
     #[private]
-    fn internal_ft_transfer(&self, token: AccountId, amount: U128, receiver_id: AccountId, deposit: Balance) -> PromiseOrValue<U128> {
-        let promise = ext_fungible_token::ext(token.clone())
+    fn internal_nft_burn(&self, token: AccountId, token_id: TokenId) -> Promise {
+        ext_non_fungible_token::ext(token.clone())
             .with_static_gas(GAS_FOR_TX)
-            .with_attached_deposit(deposit)
-            .ft_transfer(receiver_id, amount, None);
-        PromiseOrValue::from(promise)
-    }
-
-    #[private]
-    pub fn get_authorized_operation_hash(&mut self, value: String) -> Hash {
-        let mut data = Vec::new();
-        let contract_data = self.get_contract_based_hash_data();
-
-        data.append(&mut Vec::from(value.into_bytes()));
-        data.append(&mut Vec::from(contract_data));
-
-        Hash::from_slice(env::keccak256(data.as_slice()).as_slice())
-    }
-
-    #[private]
-    fn get_update_contract_hash(&mut self, code: Vec<u8>) -> Hash {
-        let mut data = Vec::new();
-        let contract_data = self.get_contract_based_hash_data();
-
-        data.append(&mut Vec::from(env::keccak256(code.as_slice()).as_slice()));
-        data.append(&mut Vec::from(contract_data));
-
-        Hash::from_slice(env::keccak256(data.as_slice()).as_slice())
+            .with_attached_deposit(TRANSFER_DEPOSIT)
+            .nft_burn(token_id.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TX + GAS_FOR_TX + GAS_FOR_TX)
+                    .nft_burn_callback(token, token_id)
+            )
     }
 
     #[private]
-    fn get_set_signer_hash(&mut self, signer_public_key: String) -> Hash {
-        Hash::from_slice(env::keccak256(Vec::from(signer_public_key.into_bytes()).as_slice()).as_slice())
+    fn internal_ft_burn(&self, token: AccountId, amount: U128) -> Promise {
+        ext_fungible_token::ext(token.clone())
+            .with_static_gas(GAS_FOR_TX)
+            .with_attached_deposit(TRANSFER_DEPOSIT)
+            .ft_burn(amount)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TX + GAS_FOR_TX + GAS_FOR_TX)
+                    .ft_burn_callback(token, amount)
+            )
     }
 
-    #[private]
-    fn get_contract_based_hash_data(&mut self) -> Vec<u8> {
-        let mut data = Vec::new();
-        data.append(&mut Vec::from(self.chain.to_string().into_bytes()));
-        data.append(&mut Vec::from(u128_to_bytes(U128::from(self.nonce.clone())).as_slice()));
-        data.append(&mut Vec::from(env::current_account_id().to_string().into_bytes()));
-        data
+    /// Hash of the operation bound to this contract, chain and the current nonce.
+    pub(crate) fn get_authorized_operation_hash(&self, operation: AuthorizedOperation) -> Hash {
+        operation.hash(&env::current_account_id(), &self.chain, self.nonce)
     }
 
     #[private]
-    pub fn handle_hash_callback(
-        &mut self,
-        #[callback_result] call_result: Result<(), PromiseError>,
-        hash: Hash,
-        token_type: TokenType,
-        receiver: AccountId,
-        sender: AccountId,
-        origin: String,
-        path: Vec<Hash>,
-        signature: String,
-        recovery_id: RecoveryID,
-        token: Option<AccountId>,
-        is_wrapped: Option<bool>,
-        token_id: Option<TokenId>,
-        amount: Option<String>,
-    ) {
-        if call_result.is_err() {
-            self.hashes.change_hash(hash, false);
-        }
-
-        match token_type {
-            TokenType::NFT => {
-                NearEvent::nft_withdrawn(vec![NftWithdrawnData::new(
-                    &token.clone().unwrap(),
-                    &token_id.clone().unwrap(),
-                    &sender.clone(),
-                    &receiver.clone(),
-                    &origin.clone(),
-                    &signature.clone(),
-                    path.clone(),
-                    recovery_id.clone(),
-                    is_wrapped.clone().unwrap(),
-                )]).emit();
-            }
-            TokenType::FT => {
-                NearEvent::ft_withdrawn(vec![FtWithdrawnData::new(
-                    &token.clone().unwrap(),
-                    &amount.clone().unwrap(),
-                    &sender.clone(),
-                    &receiver.clone(),
-                    &origin.clone(),
-                    &signature.clone(),
-                    path.clone(),
-                    recovery_id.clone(),
-                    is_wrapped.clone().unwrap(),
-                )]).emit();
-            }
-            TokenType::Native => {
-                NearEvent::native_withdrawn(vec![NativeWithdrawnData::new(
-                    &amount.clone().unwrap(),
-                    &sender.clone(),
-                    &receiver.clone(),
-                    &origin.clone(),
-                    &signature.clone(),
-                    path.clone(),
-                    recovery_id.clone(),
-                )]).emit();
-            }
-        }
+    pub fn handle_hash_callback(&mut self, withdrawal: Withdrawal, sender: AccountId, root: Hash) {
+        self.internal_resolve_withdrawal(get_promise_result(0), &withdrawal, &sender, root);
     }
 
     #[private]
-    pub fn check_signature(&mut self, msg: Hash, signature: Secp256K1Signature, recovery_id: RecoveryID) {
-        verify_ecdsa_signature(self.signer.clone(), msg, signature, recovery_id);
+    pub fn check_signature(&mut self, msg: Hash, signatures: Vec<RecoverableSignature>) {
+        self.signers.verify(msg, &signatures);
     }
 
     #[private]
-    pub fn check_signature_and_increment_nonce(&mut self, msg: Hash, signature: Secp256K1Signature, recovery_id: RecoveryID) {
-        self.check_signature(msg, signature, recovery_id);
+    pub fn check_signature_and_increment_nonce(&mut self, msg: Hash, signatures: Vec<RecoverableSignature>) {
+        self.check_signature(msg, signatures);
         self.nonce += 1;
     }
 
@@ -523,11 +492,12 @@ impl Bridge {
         token: AccountId,
         token_id: TokenId,
         receiver_id: AccountId,
-        is_wrapped: bool,
         origin: String,
         path: Vec<Hash>,
-        signature: String,
-        recovery_id: RecoveryID,
+        bundle: Option<Bundle>,
+        msg: Option<String>,
+        // Caller of `nft_withdraw`, the predecessor of the callback is the bridge itself
+        sender: AccountId,
     ) -> PromiseOrValue<bool> {
         if call_result.is_err() {
             env::panic_str("failed to get non fungible token metadata");
@@ -549,12 +519,12 @@ impl Bridge {
             token.clone(),
             token_id.clone(),
             receiver_id.clone(),
-            metadata.clone().unwrap(),
-            is_wrapped.clone(),
+            NftMetadata::from(metadata.unwrap()),
             origin.clone(),
             path.clone(),
-            signature.clone(),
-            recovery_id.clone(),
+            bundle,
+            msg,
+            sender,
         )
     }
 
@@ -565,85 +535,541 @@ impl Bridge {
         token: AccountId,
         token_id: TokenId,
         receiver_id: AccountId,
-        token_metadata: TokenMetadata,
-        is_wrapped: bool,
+        token_metadata: NftMetadata,
         origin: String,
         path: Vec<Hash>,
-        signature: String,
-        recovery_id: RecoveryID,
+        bundle: Option<Bundle>,
+        msg: Option<String>,
+        sender: AccountId,
     ) -> PromiseOrValue<bool> {
-        let sign = Secp256K1Signature::from_hex(signature.clone());
-        let origin_hash = Hash::from_hex(origin.clone());
-
-        let content = ContentNode::new(
-            origin_hash,
-            env::current_account_id(),
-            self.chain.clone(),
-            TransferFullMetaOperation::new_nft_transfer(
-                token_id.clone(),
-                Some(token.clone()),
-                token_metadata.title.as_ref().unwrap().to_string(),
-                token_metadata.media.as_ref().unwrap().to_string(),
-                base64::encode(token_metadata.media_hash.clone().unwrap().0),
-            ).get_data(),
-            Some(receiver_id.clone()),
-        );
+        let withdrawal = Withdrawal::new(origin, receiver_id, WithdrawalKind::NFT { token, token_id, token_metadata, msg }, bundle);
+        let root = self.internal_verify_withdrawal(&withdrawal, &path);
 
-        self.check_signature(get_merkle_root(content, &path.clone()), sign, recovery_id.clone());
-        self.hashes.check_hash(origin_hash.clone());
+        PromiseOrValue::from(self.internal_withdraw(withdrawal, root, env::attached_deposit(), sender))
+    }
+}
 
-        if is_wrapped {
-            let promise = ext_non_fungible_token::ext(token.clone())
-                .with_static_gas(GAS_FOR_TX)
-                .with_attached_deposit(env::attached_deposit())
-                .nft_mint(token_id.clone(), receiver_id.clone(), token_metadata.clone(), None)
-                .then(
-                    Self::ext(env::current_account_id())
-                        .with_static_gas(GAS_FOR_TX)
-                        .handle_hash_callback(
-                            origin_hash.clone(),
-                            TokenType::NFT,
-                            receiver_id.clone(),
-                            env::predecessor_account_id(),
-                            origin.clone(),
-                            path.clone(),
-                            signature.clone(),
-                            recovery_id.clone(),
-                            Some(token.clone()),
-                            Some(is_wrapped.clone()),
-                            Some(token_id.clone()),
-                            None,
-                        )
-                );
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::collections::HashMap;
+
+    use near_sdk::testing_env;
+    use near_sdk::serde_json::json;
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+
+    use shared::{AddressFormat, Data};
+
+    use crate::limits::{LimitPolicy, TokenLimits, WindowLimit};
+    use crate::locked::LockedLedger;
+    use crate::merkle::TransferFullMetaOperation;
+    use crate::pending::WithdrawalStatus;
+    use crate::timelock::{QueueStatus, Timelock};
+    use crate::tokens::{TokenInfo, TokenMode};
+
+    use super::*;
+
+    const CHAIN: &str = "Near";
+    const SIGNERS: [&str; 3] = [
+        "KntdnyUx5qDhi3p83DikQxX6uM1MuL1ktfChXLA7d2R3HMvbUbTCJzcTAmz5vj7UdiZAdH42iJDKXxVzgpCwwLo",
+        "4qLJVvdCUJHcoG8FWLMREsESL2aMVhdg2VDuhW4hkDUfFA52FFyQyRKywBSak9BMzdZkppPHmsSUKHFtRFe4yz8",
+        "5RckuVnSmZV25zm3QtPkubzScJG6CP3m8xiaB5hqvgVou62VyLh8dQd4yLKSDVR2LaiVs1tUH1VTAsx11ZajWWUZ",
+    ];
+    // `set_fee_contract(accounts(3))` at nonce 0, signed by the first two signers and by an outsider
+    const FEE_CONTRACT_SIGNATURES: [(&str, u8); 3] = [
+        ("0xfd39176b4e51cdd0e99dfb741f58f560dea61d583addaaa87ab31af38971c5b1452b182275fa0eadb08fcbfede7db49668d7b41ae1d4eef28f92c7617933b4c3", 0),
+        ("0xaff538b5c4e8bb11324ae4b3426c7ba81c535ca5295c6107def5b8a3feb308d53231456e63b0fa981c300d0e54c38eef8bfa3cfd51fbff098ee045d23a1f72e5", 0),
+        ("0x5a3a50b01dabf808748fbf14debf866baeffba4a48cc3bbcf7ec0282fcc4ddb7167f062ab77db4087dabd572bef8af66b42ac6f06592bc60c001d3f8e7e22325", 0),
+    ];
+    // `migrate_assets(accounts(4), None)` at nonce 0, signed by the first two signers
+    const MIGRATE_SIGNATURES: [(&str, u8); 2] = [
+        ("0x04dc69432abc8c96b56bb2cdcbd76397fddc75dc3b5f5497354349a7937fe6814402ee2ea48a25fefc01ecc4bebff9289e292e4b2b63919a59d00bc641d37b5e", 1),
+        ("0xf8733ccc4b0d20a4a4ac8d6e756be80e1bc80dbf26f8a345d704317719261cfd6b7d3bf622965d9465b7a32b63db28fc50158da331ef5951bf1ee1efa4415b2e", 0),
+    ];
+    // `seed_reserves(None, Some(100), None)` at nonce 0, signed by the first two signers
+    const SEED_SIGNATURES: [(&str, u8); 2] = [
+        ("0x4a03e9fc7e2087b4c574f724351409124038ad8ec01d19d4d13fd51c218974851370ba3c8d4cbba12c057061f75ff07e4df9ccd60240f8103b15739c29b77f2f", 1),
+        ("0xabce943d8503943fd98c8708c77b6372e57b4710bfedaabc5bb02332db3f7d732739821d26a8b9c264436a7d81bc1b03fd2d853ab5715aee985db5ff95196ca0", 0),
+    ];
+    // `seed_reserves(Some(accounts(5)), Some(1000), None)` at nonce 0, signed by the first two signers
+    const SEED_WRAPPED_SIGNATURES: [(&str, u8); 2] = [
+        ("0xbd94066f2e4009fade2d2f19b5963fcbf547641024c724ccf84cb23e40c67aa6485b1174b9a1d924e1a96ab5162cdb7d0ede0e8d269e6672897630e1896734a5", 0),
+        ("0xe863412f48bcfe0526f2587ed0c57147b759b511b5a08772cb7b5f4b75807b0b4f004b4a69f0ec88a2d8dbddf4b5c1bc42aa4b17c1f2497c585bfa3cf17b1007", 1),
+    ];
+    const ORIGIN: &str = "0x0a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20212223242526272829";
+    const ROOT: &str = "0x2a2b2c2d2e2f303132333435363738393a3b3c3d3e3f40414243444546474849";
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .current_account_id(accounts(0))
+            .signer_account_id(predecessor_account_id.clone())
+            .predecessor_account_id(predecessor_account_id);
+        builder
+    }
 
-            PromiseOrValue::from(promise)
-        } else {
-            let promise = self.internal_nft_transfer(
-                token.clone(),
-                token_id.clone(),
-                receiver_id.clone(),
-                env::attached_deposit(),
-            ).then(
-                Self::ext(env::current_account_id())
-                    .with_static_gas(GAS_FOR_TX)
-                    .handle_hash_callback(
-                        origin_hash.clone(),
-                        TokenType::NFT,
-                        receiver_id.clone(),
-                        env::predecessor_account_id(),
-                        origin.clone(),
-                        path.clone(),
-                        signature.clone(),
-                        recovery_id.clone(),
-                        Some(token.clone()),
-                        Some(is_wrapped.clone()),
-                        Some(token_id.clone()),
-                        None,
-                    )
-            );
+    fn signers() -> Vec<SignerPublicKey> {
+        SIGNERS.iter().map(|signer| SignerPublicKey::new(signer.to_string())).collect()
+    }
 
-            PromiseOrValue::from(promise)
+    fn signature((signature, recovery_id): (&str, u8)) -> RecoverableSignature {
+        serde_json::from_value(json!({
+            "signature": signature,
+            "recovery_id": recovery_id,
+        })).unwrap()
+    }
+
+    fn new_bridge() -> Bridge {
+        Bridge::new(signers(), 2, accounts(1), CHAIN.to_string())
+    }
+
+    fn native_withdrawal(amount: Balance) -> Withdrawal {
+        Withdrawal::new(ORIGIN.to_string(), accounts(2), WithdrawalKind::Native { amount: U128(amount) }, None)
+    }
+
+    #[test]
+    fn test_threshold_signatures() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+
+        contract.set_fee_contract(accounts(3), vec![
+            signature(FEE_CONTRACT_SIGNATURES[0]),
+            signature(FEE_CONTRACT_SIGNATURES[1]),
+        ]);
+        assert_eq!(contract.get_fee_contract(), accounts(3));
+        assert_eq!(contract.nonce, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Signers: not enough signatures")]
+    fn test_threshold_not_reached() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+
+        contract.set_fee_contract(accounts(3), vec![signature(FEE_CONTRACT_SIGNATURES[0])]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Signers: duplicated signature")]
+    fn test_duplicated_signature() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+
+        contract.set_fee_contract(accounts(3), vec![
+            signature(FEE_CONTRACT_SIGNATURES[0]),
+            signature(FEE_CONTRACT_SIGNATURES[0]),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Signers: recovered key is not a member of the signer set")]
+    fn test_signature_of_non_member() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+
+        contract.set_fee_contract(accounts(3), vec![
+            signature(FEE_CONTRACT_SIGNATURES[0]),
+            signature(FEE_CONTRACT_SIGNATURES[2]),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Signers: recovered key is not a member of the signer set")]
+    fn test_signatures_replay() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+
+        let signatures = vec![
+            signature(FEE_CONTRACT_SIGNATURES[0]),
+            signature(FEE_CONTRACT_SIGNATURES[1]),
+        ];
+        contract.set_fee_contract(accounts(3), signatures.clone());
+        contract.set_fee_contract(accounts(3), signatures);
+    }
+
+    #[test]
+    fn test_failed_withdrawal_is_pending() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.locked.lock_native(100);
+
+        let withdrawal = native_withdrawal(100);
+        contract.internal_unlock_withdrawal(&withdrawal);
+        assert_eq!(contract.locked.native, 0);
+
+        contract.internal_resolve_withdrawal(None, &withdrawal, &accounts(2), Hash::from_hex(ROOT.to_string()));
+
+        let failed = contract.get_failed_withdrawals(None, None);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].withdrawal.origin, ORIGIN);
+        assert_eq!(failed[0].root, ROOT);
+        assert_eq!(failed[0].status, WithdrawalStatus::Failed);
+        assert_eq!(failed[0].attempts, 1);
+        assert_eq!(contract.locked.native, 100);
+    }
+
+    #[test]
+    fn test_delivered_withdrawal_is_not_pending() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.locked.lock_native(100);
+
+        let withdrawal = native_withdrawal(100);
+        contract.internal_unlock_withdrawal(&withdrawal);
+        contract.internal_resolve_withdrawal(None, &withdrawal, &accounts(2), Hash::from_hex(ROOT.to_string()));
+
+        contract.pending.start_retry(&withdrawal.origin);
+        contract.internal_unlock_withdrawal(&withdrawal);
+        contract.internal_resolve_withdrawal(Some(vec![]), &withdrawal, &accounts(2), Hash::from_hex(ROOT.to_string()));
+
+        assert!(contract.get_pending_withdrawal(ORIGIN.to_string()).is_none());
+        assert_eq!(contract.locked.native, 0);
+    }
+
+    #[test]
+    fn test_failed_withdrawal_releases_limit_usage() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.limits.set_limits(&None, Some(TokenLimits {
+            max_per_withdrawal: None,
+            windows: vec![WindowLimit { duration: U64(3600), max_amount: U128(1000) }],
+            policy: LimitPolicy::Reject,
+            delay: U64(0),
+        }));
+        contract.locked.lock_native(100);
+
+        let withdrawal = native_withdrawal(100);
+        contract.internal_unlock_withdrawal(&withdrawal);
+        contract.internal_record_limit_usage(&withdrawal);
+        assert_eq!(contract.get_limit_usage(None)[0].used, U128(100));
+
+        contract.internal_resolve_withdrawal(None, &withdrawal, &accounts(2), Hash::from_hex(ROOT.to_string()));
+        assert_eq!(contract.get_limit_usage(None)[0].used, U128(0));
+    }
+
+    #[test]
+    fn test_retry_over_limit_is_queued() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.limits.set_limits(&None, Some(TokenLimits {
+            max_per_withdrawal: Some(U128(50)),
+            windows: vec![],
+            policy: LimitPolicy::Delay,
+            delay: U64(60),
+        }));
+        contract.locked.lock_native(100);
+
+        let withdrawal = native_withdrawal(100);
+        contract.internal_unlock_withdrawal(&withdrawal);
+        contract.internal_resolve_withdrawal(None, &withdrawal, &accounts(2), Hash::from_hex(ROOT.to_string()));
+
+        testing_env!(context.attached_deposit(1).storage_usage(env::storage_usage()).build());
+        contract.retry_withdrawal(ORIGIN.to_string());
+
+        assert!(contract.get_pending_withdrawal(ORIGIN.to_string()).is_none());
+        assert_eq!(contract.get_queued_withdrawal(ORIGIN.to_string()).unwrap().status, QueueStatus::Queued);
+        assert_eq!(contract.locked.native, 100);
+    }
+
+    #[test]
+    fn test_cancelled_withdrawal_is_queued_again() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.guardians.add_guardian(&accounts(3));
+        contract.queue.set_timelock(&None, Some(Timelock { threshold: U128(100), delay: U64(60) }));
+
+        let withdrawal = native_withdrawal(100);
+        contract.hashes.check_hash(withdrawal.origin_hash());
+        assert!(!contract.internal_check_withdrawal_delay(&withdrawal, Hash::from_hex(ROOT.to_string()), 0));
+
+        testing_env!(context.predecessor_account_id(accounts(3)).storage_usage(env::storage_usage()).build());
+        contract.cancel_queued_withdrawal(ORIGIN.to_string());
+        assert_eq!(contract.get_queued_withdrawal(ORIGIN.to_string()).unwrap().status, QueueStatus::Cancelled);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).storage_usage(env::storage_usage()).build());
+        contract.hashes.check_hash(withdrawal.origin_hash());
+        assert!(!contract.internal_check_withdrawal_delay(&withdrawal, Hash::from_hex(ROOT.to_string()), 0));
+        assert_eq!(contract.get_queued_withdrawal(ORIGIN.to_string()).unwrap().status, QueueStatus::Queued);
+    }
+
+    #[test]
+    #[should_panic(expected = "Queue: withdrawal already queued")]
+    fn test_queued_withdrawal_is_not_queued_twice() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.queue.set_timelock(&None, Some(Timelock { threshold: U128(100), delay: U64(60) }));
+
+        let withdrawal = native_withdrawal(100);
+        contract.internal_check_withdrawal_delay(&withdrawal, Hash::from_hex(ROOT.to_string()), 0);
+        contract.internal_check_withdrawal_delay(&withdrawal, Hash::from_hex(ROOT.to_string()), 0);
+    }
+
+    #[test]
+    fn test_deposit_hash_chain() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+
+        let first = contract.internal_commit_deposit(
+            None,
+            None,
+            Some(U128(100)),
+            accounts(2),
+            "0xreceiver".to_string(),
+            "Ethereum".to_string(),
+            None,
+            None,
+        );
+        let second = contract.internal_commit_deposit(
+            None,
+            None,
+            Some(U128(200)),
+            accounts(2),
+            "0xreceiver".to_string(),
+            "Ethereum".to_string(),
+            None,
+            None,
+        );
+        assert_eq!((first, second), (0, 1));
+        assert_eq!(contract.get_deposit_count(), U64(2));
+
+        let mut accumulator = Hash::new();
+        for nonce in 0..2 {
+            let commitment = contract.get_deposit_commitment(U64(nonce)).unwrap();
+            let leaf = Hash::from_hex(commitment.leaf);
+            accumulator = Hash::from_slice(&env::keccak256(&[accumulator.to_slice(), leaf.to_slice()].concat()));
+            assert_eq!(commitment.accumulator, accumulator.as_str());
         }
+        assert_eq!(contract.deposits.accumulator, accumulator);
+    }
+
+    #[test]
+    fn test_normalize_evm_receiver() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.chains.set_chain(&"Ethereum".to_string(), Some(AddressFormat::Evm));
+
+        let checksummed = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let chain = "Ethereum".to_string();
+        assert_eq!(contract.internal_normalize_receiver(&chain, &checksummed.to_lowercase()), checksummed);
+        assert_eq!(contract.internal_normalize_receiver(&chain, checksummed), checksummed);
+        assert_eq!(AddressFormat::Evm.normalize("0x5aaeb6053F3E94C9b9A09f33669435E7Ef1BeAed"), None);
+    }
+
+    #[test]
+    fn test_operation_hash_is_domain_separated() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let operation = || AuthorizedOperation::new(OperationType::SetFeeContract).account(&accounts(3));
+
+        let hash = operation().hash(&accounts(0), CHAIN, 0);
+        assert_ne!(hash, operation().hash(&accounts(0), CHAIN, 1));
+        assert_ne!(hash, operation().hash(&accounts(0), "Ethereum", 0));
+        assert_ne!(hash, operation().hash(&accounts(1), CHAIN, 0));
+        assert_ne!(hash, AuthorizedOperation::new(OperationType::SetThreshold).account(&accounts(3)).hash(&accounts(0), CHAIN, 0));
+    }
+
+    #[test]
+    fn test_failed_storage_check_is_not_paid() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+
+        let kind = WithdrawalKind::FT { token: accounts(5), amount: U128(50), msg: None };
+        contract.ft_storage_callback(
+            Err(PromiseError::Failed),
+            Err(PromiseError::Failed),
+            Withdrawal::new(ORIGIN.to_string(), accounts(2), kind, None),
+            accounts(2),
+            U128(100),
+            accounts(3),
+        );
+
+        assert_eq!(get_logs(), vec![format!("Storage: failed to check the registration of {}, it is not paid", accounts(2))]);
     }
-}
 
+    #[test]
+    fn test_missing_and_empty_message_leaves_differ() {
+        let royalty = HashMap::from([(accounts(3), 500u32)]);
+        let leaf = |msg: Option<String>| TransferFullMetaOperation::new_nft_transfer(
+            "1".to_string(),
+            Some(accounts(5)),
+            "Title".to_string(),
+            "https://example.com/1.png".to_string(),
+            "".to_string(),
+            msg,
+            Some(royalty.clone()),
+        ).get_data();
+
+        assert_ne!(leaf(None), leaf(Some("".to_string())));
+    }
+
+    #[test]
+    #[should_panic(expected = "Disallowed while contract is unpaused")]
+    fn test_migrate_assets_unpaused() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+
+        contract.migrate_assets(accounts(4), None, vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Migration: delay has not passed")]
+    fn test_migrate_assets_before_delay() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        <Bridge as Pause>::pause(&mut contract);
+        contract.migration.announce(accounts(4));
+
+        contract.migrate_assets(accounts(4), None, vec![
+            signature(MIGRATE_SIGNATURES[0]),
+            signature(MIGRATE_SIGNATURES[1]),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rescue: wrapped tokens can not be rescued")]
+    fn test_rescue_wrapped_token() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.tokens.add_token(TokenInfo {
+            token: accounts(5),
+            token_type: TokenType::FT,
+            mode: TokenMode::Wrapped,
+            chain: "Ethereum".to_string(),
+            decimals: 18,
+        });
+
+        contract.rescue_tokens(accounts(5), Some(U128(1)), None, accounts(2), vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rescue: only 50 tokens are not locked")]
+    fn test_rescue_withdrawal_in_flight() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.tokens.add_token(TokenInfo {
+            token: accounts(5),
+            token_type: TokenType::FT,
+            mode: TokenMode::Locked,
+            chain: CHAIN.to_string(),
+            decimals: 18,
+        });
+        contract.locked.lock_ft(&accounts(5), 150);
+
+        let kind = WithdrawalKind::FT { token: accounts(5), amount: U128(50), msg: None };
+        contract.internal_unlock_withdrawal(&Withdrawal::new(ORIGIN.to_string(), accounts(2), kind, None));
+
+        contract.rescue_ft_callback(Ok(U128(200)), accounts(5), U128(60), accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reserves: withdrawal exceeds the locked balance")]
+    fn test_withdrawal_exceeds_locked_balance() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.locked.lock_native(50);
+
+        contract.internal_unlock_withdrawal(&native_withdrawal(100));
+    }
+
+    #[test]
+    fn test_seed_reserves() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.locked = LockedLedger::unseeded();
+
+        contract.internal_unlock_withdrawal(&native_withdrawal(100));
+        contract.locked.lock_native(30);
+        assert!(!contract.get_reserves(None).seeded);
+
+        contract.seed_reserves(None, Some(U128(100)), None, vec![
+            signature(SEED_SIGNATURES[0]),
+            signature(SEED_SIGNATURES[1]),
+        ]);
+
+        let reserves = contract.get_reserves(None);
+        assert!(reserves.seeded);
+        assert_eq!(reserves.locked, U128(100));
+    }
+
+    #[test]
+    fn test_seed_wrapped_supply() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.locked = LockedLedger::unseeded();
+        contract.tokens.add_token(TokenInfo {
+            token: accounts(5),
+            token_type: TokenType::FT,
+            mode: TokenMode::Wrapped,
+            chain: "Ethereum".to_string(),
+            decimals: 18,
+        });
+        assert!(!contract.get_wrapped_supply(accounts(5)).seeded);
+
+        contract.seed_reserves(Some(accounts(5)), Some(U128(1000)), None, vec![
+            signature(SEED_WRAPPED_SIGNATURES[0]),
+            signature(SEED_WRAPPED_SIGNATURES[1]),
+        ]);
+
+        let supply = contract.get_wrapped_supply(accounts(5));
+        assert!(supply.seeded);
+        assert_eq!(supply.supply, U128(1000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Supply: mint cap exceeded")]
+    fn test_mint_over_cap() {
+        let context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = new_bridge();
+        contract.wrapped_supply.set_cap(&accounts(5), Some(1000));
+
+        contract.wrapped_supply.mint(&accounts(5), 600);
+        assert_eq!(contract.wrapped_supply.get_supply(&accounts(5)), 600);
+        contract.wrapped_supply.burn(&accounts(5), 100);
+        contract.wrapped_supply.mint(&accounts(5), 500);
+        assert_eq!(contract.wrapped_supply.get_supply(&accounts(5)), 1000);
+
+        contract.wrapped_supply.mint(&accounts(5), 1);
+    }
+
+    #[test]
+    fn test_migrate_old_state() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let state = (
+            SignerPublicKey::new(SIGNERS[0].to_string()),
+            Hashes::new(),
+            CHAIN.to_string(),
+            accounts(1),
+            7u128,
+        );
+        env::storage_write(b"STATE", &state.try_to_vec().unwrap());
+
+        let contract = Bridge::migrate();
+        assert_eq!(contract.get_signers(), SignerSet::new(vec![SignerPublicKey::new(SIGNERS[0].to_string())], 1));
+        assert_eq!(contract.get_chain(), CHAIN);
+        assert_eq!(contract.get_fee_contract(), accounts(1));
+        assert_eq!(contract.nonce, 7);
+        assert!(!contract.get_reserves(None).seeded);
+    }
+}
@@ -0,0 +1,211 @@
+use near_sdk::{AccountId, Balance, env, near_bindgen};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::{U128, U64};
+use serde::{Deserialize, Serialize};
+
+use shared::{AuthorizedOperation, OperationType, RecoverableSignature};
+
+use crate::timelock::NANOS_IN_SECOND;
+
+use super::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub enum LimitPolicy {
+    /// Withdrawals over the limit fail.
+    Reject,
+    /// Withdrawals over the limit are put into the withdrawal queue.
+    Delay,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub struct WindowLimit {
+    // Window length in seconds
+    pub duration: U64,
+    pub max_amount: U128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub struct TokenLimits {
+    pub max_per_withdrawal: Option<U128>,
+    pub windows: Vec<WindowLimit>,
+    pub policy: LimitPolicy,
+    // Seconds a withdrawal over the limit waits in the queue before it can be claimed
+    pub delay: U64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WindowUsage {
+    pub duration: U64,
+    pub max_amount: U128,
+    pub used: U128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct UsageRecord {
+    pub timestamp: u64,
+    pub amount: Balance,
+}
+
+/// Withdrawal limits keyed by the token account, `None` stands for native NEAR.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LimitsManager {
+    pub limits: LookupMap<Option<AccountId>, TokenLimits>,
+    pub usage: LookupMap<Option<AccountId>, Vec<UsageRecord>>,
+}
+
+impl LimitsManager {
+    pub fn new() -> Self {
+        Self {
+            limits: LookupMap::new(b"limits".to_vec()),
+            usage: LookupMap::new(b"limits_usage".to_vec()),
+        }
+    }
+
+    pub fn set_limits(&mut self, token: &Option<AccountId>, limits: Option<TokenLimits>) {
+        match limits {
+            Some(limits) => {
+                for window in limits.windows.iter() {
+                    assert!(window.duration.0 > 0, "Limits: window duration must be greater than zero");
+                }
+                self.limits.insert(token, &limits);
+            }
+            None => {
+                self.limits.remove(token);
+                self.usage.remove(token);
+            }
+        }
+    }
+
+    pub fn get_limits(&self, token: &Option<AccountId>) -> Option<TokenLimits> {
+        self.limits.get(token)
+    }
+
+    pub fn get_usage(&self, token: &Option<AccountId>) -> Vec<WindowUsage> {
+        let limits = match self.limits.get(token) {
+            Some(limits) => limits,
+            None => return Vec::new(),
+        };
+        let records = self.usage.get(token).unwrap_or_default();
+
+        limits.windows.iter().map(|window| WindowUsage {
+            duration: window.duration,
+            max_amount: window.max_amount,
+            used: U128(used_in_window(&records, window.duration.0)),
+        }).collect()
+    }
+
+    /// Returns `false` if the withdrawal has to be queued and panics if it has to be rejected. The
+    /// amount is recorded only when the withdrawal is executed, see `record`.
+    pub fn check(&self, token: &Option<AccountId>, amount: Balance) -> bool {
+        let limits = match self.limits.get(token) {
+            Some(limits) => limits,
+            None => return true,
+        };
+        let records = self.usage.get(token).unwrap_or_default();
+
+        let exceeded = limits.max_per_withdrawal.map_or(false, |max| amount > max.0)
+            || limits.windows.iter().any(|window| {
+            used_in_window(&records, window.duration.0).saturating_add(amount) > window.max_amount.0
+        });
+
+        if exceeded {
+            if limits.policy == LimitPolicy::Reject {
+                env::panic_str("Limits: withdrawal exceeds the limit");
+            }
+            return false;
+        }
+
+        true
+    }
+
+    /// Records the amount of an executed withdrawal.
+    pub fn record(&mut self, token: &Option<AccountId>, amount: Balance) {
+        let limits = match self.limits.get(token) {
+            Some(limits) => limits,
+            None => return,
+        };
+        let mut records = self.usage.get(token).unwrap_or_default();
+
+        // Keep only the records that are still inside the longest window
+        let longest = limits.windows.iter().map(|window| window.duration.0).max().unwrap_or(0);
+        records.retain(|record| is_in_window(record, longest));
+        records.push(UsageRecord { timestamp: env::block_timestamp(), amount });
+        self.usage.insert(token, &records);
+    }
+
+    /// Takes back the amount of a withdrawal which failed, starting from the latest records.
+    pub fn release(&mut self, token: &Option<AccountId>, amount: Balance) {
+        let mut records = match self.usage.get(token) {
+            Some(records) => records,
+            None => return,
+        };
+
+        let mut left = amount;
+        for record in records.iter_mut().rev() {
+            let released = left.min(record.amount);
+            record.amount -= released;
+            left -= released;
+            if left == 0 {
+                break;
+            }
+        }
+        records.retain(|record| record.amount > 0);
+        self.usage.insert(token, &records);
+    }
+}
+
+fn is_in_window(record: &UsageRecord, duration: u64) -> bool {
+    record.timestamp + duration * NANOS_IN_SECOND > env::block_timestamp()
+}
+
+fn used_in_window(records: &[UsageRecord], duration: u64) -> Balance {
+    records.iter()
+        .filter(|record| is_in_window(record, duration))
+        .fold(0, |used, record| used.saturating_add(record.amount))
+}
+
+#[near_bindgen]
+impl Bridge {
+    /// Sets the withdrawal limits of the token, `None` token stands for native NEAR and `None`
+    /// limits remove them.
+    pub fn set_token_limits(
+        &mut self,
+        token: Option<AccountId>,
+        limits: Option<TokenLimits>,
+        signatures: Vec<RecoverableSignature>,
+    ) {
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::SetTokenLimits).borsh(&token).borsh(&limits)
+        );
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.limits.set_limits(&token, limits);
+    }
+
+    pub fn get_token_limits(&self, token: Option<AccountId>) -> Option<TokenLimits> {
+        self.limits.get_limits(&token)
+    }
+
+    /// Returns the amount withdrawn in every configured window of the token at the moment.
+    pub fn get_limit_usage(&self, token: Option<AccountId>) -> Vec<WindowUsage> {
+        self.limits.get_usage(&token)
+    }
+}
+
+impl Bridge {
+    /// Records the withdrawn amount in the limit windows when the delivery starts, the NFTs are
+    /// not limited.
+    pub(crate) fn internal_record_limit_usage(&mut self, withdrawal: &Withdrawal) {
+        if let Some((token, amount)) = withdrawal.limited_amount() {
+            self.limits.record(&token, amount);
+        }
+    }
+
+    /// Releases the limit usage of a failed delivery, so its retry does not count twice.
+    pub(crate) fn internal_release_limit_usage(&mut self, withdrawal: &Withdrawal) {
+        if let Some((token, amount)) = withdrawal.limited_amount() {
+            self.limits.release(&token, amount);
+        }
+    }
+}
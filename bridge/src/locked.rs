@@ -0,0 +1,284 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, Balance, env, near_bindgen, require};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, LookupSet};
+use near_sdk::json_types::U128;
+use serde::{Deserialize, Serialize};
+
+use shared::{AuthorizedOperation, OperationType, RecoverableSignature, TokenType};
+
+use super::*;
+
+/// Expected balances of the native NEAR and the locked tokens, they back the supply bridged to
+/// other chains. Deposits add to the ledger, withdrawals take from it when the delivery starts and
+/// give back if it fails.
+///
+/// A ledger converted from the state before it existed starts empty, every token (`None` for native
+/// NEAR) is seeded once with the reserves locked until then. The reserve checks are off for a token
+/// until it is seeded.
+///
+/// Withdrawn FT amounts and NFTs stay in flight until the delivery resolves, they have left the
+/// ledger but not the bridge account yet.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LockedLedger {
+    pub native: Balance,
+    pub ft: LookupMap<AccountId, Balance>,
+    pub nft: LookupSet<(AccountId, TokenId)>,
+    pub nft_count: LookupMap<AccountId, u64>,
+    pub ft_in_flight: LookupMap<AccountId, Balance>,
+    pub nft_in_flight: LookupSet<(AccountId, TokenId)>,
+    pub seeding_required: bool,
+    pub seeded: LookupSet<Option<AccountId>>,
+}
+
+/// Locked reserves of a token, `token` is `None` for native NEAR.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Reserves {
+    pub token: Option<AccountId>,
+    pub token_type: TokenType,
+    // Chain the token originates from
+    pub chain: String,
+    // Locked amount of native NEAR or a fungible token, the number of locked NFTs
+    pub locked: U128,
+    // False until the reserves locked before the ledger existed are seeded, `locked` is incomplete then
+    pub seeded: bool,
+}
+
+impl LockedLedger {
+    pub fn new() -> Self {
+        Self {
+            native: 0,
+            ft: LookupMap::new(b"locked_ft".to_vec()),
+            nft: LookupSet::new(b"locked_nft".to_vec()),
+            nft_count: LookupMap::new(b"locked_nft_count".to_vec()),
+            ft_in_flight: LookupMap::new(b"locked_ft_in_flight".to_vec()),
+            nft_in_flight: LookupSet::new(b"locked_nft_in_flight".to_vec()),
+            seeding_required: false,
+            seeded: LookupSet::new(b"locked_seeded".to_vec()),
+        }
+    }
+
+    /// Ledger of a bridge which locked assets before the ledger existed, see `seed_reserves`.
+    pub fn unseeded() -> Self {
+        Self {
+            seeding_required: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn is_seeded(&self, token: &Option<AccountId>) -> bool {
+        !self.seeding_required || self.seeded.contains(token)
+    }
+
+    pub fn lock_native(&mut self, amount: Balance) {
+        self.native += amount;
+    }
+
+    pub fn unlock_native(&mut self, amount: Balance) {
+        if self.is_seeded(&None) {
+            require!(amount <= self.native, "Reserves: withdrawal exceeds the locked balance");
+        }
+        self.native = self.native.saturating_sub(amount);
+    }
+
+    pub fn get_ft(&self, token: &AccountId) -> Balance {
+        self.ft.get(token).unwrap_or(0)
+    }
+
+    pub fn lock_ft(&mut self, token: &AccountId, amount: Balance) {
+        self.ft.insert(token, &(self.get_ft(token) + amount));
+    }
+
+    pub fn unlock_ft(&mut self, token: &AccountId, amount: Balance) {
+        let locked = self.get_ft(token);
+        if self.is_seeded(&Some(token.clone())) {
+            require!(amount <= locked, "Reserves: withdrawal exceeds the locked balance");
+        }
+        self.ft.insert(token, &locked.saturating_sub(amount));
+    }
+
+    pub fn get_nft_count(&self, token: &AccountId) -> u64 {
+        self.nft_count.get(token).unwrap_or(0)
+    }
+
+    pub fn is_nft_locked(&self, token: &AccountId, token_id: &TokenId) -> bool {
+        self.nft.contains(&(token.clone(), token_id.clone()))
+    }
+
+    pub fn lock_nft(&mut self, token: &AccountId, token_id: &TokenId) {
+        if self.nft.insert(&(token.clone(), token_id.clone())) {
+            self.nft_count.insert(token, &(self.get_nft_count(token) + 1));
+        }
+    }
+
+    pub fn unlock_nft(&mut self, token: &AccountId, token_id: &TokenId) {
+        let removed = self.nft.remove(&(token.clone(), token_id.clone()));
+        require!(removed || !self.is_seeded(&Some(token.clone())), "Reserves: token is not locked");
+
+        if removed {
+            self.nft_count.insert(token, &(self.get_nft_count(token) - 1));
+        }
+    }
+
+    pub fn get_ft_in_flight(&self, token: &AccountId) -> Balance {
+        self.ft_in_flight.get(token).unwrap_or(0)
+    }
+
+    pub fn send_ft(&mut self, token: &AccountId, amount: Balance) {
+        self.ft_in_flight.insert(token, &(self.get_ft_in_flight(token) + amount));
+    }
+
+    pub fn settle_ft(&mut self, token: &AccountId, amount: Balance) {
+        self.ft_in_flight.insert(token, &self.get_ft_in_flight(token).saturating_sub(amount));
+    }
+
+    pub fn is_nft_in_flight(&self, token: &AccountId, token_id: &TokenId) -> bool {
+        self.nft_in_flight.contains(&(token.clone(), token_id.clone()))
+    }
+
+    pub fn send_nft(&mut self, token: &AccountId, token_id: &TokenId) {
+        self.nft_in_flight.insert(&(token.clone(), token_id.clone()));
+    }
+
+    pub fn settle_nft(&mut self, token: &AccountId, token_id: &TokenId) {
+        self.nft_in_flight.remove(&(token.clone(), token_id.clone()));
+    }
+
+    /// Clears the fungible token balance, the tokens were moved to a successor bridge.
+    pub fn clear_ft(&mut self, token: &AccountId) {
+        self.ft.remove(token);
+    }
+
+    /// Removes the NFT if it is locked, the token was moved to a successor bridge.
+    pub fn clear_nft(&mut self, token: &AccountId, token_id: &TokenId) {
+        if self.is_nft_locked(token, token_id) {
+            self.unlock_nft(token, token_id);
+        }
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    /// Returns the locked reserves of the token, `None` stands for native NEAR. Compare them with
+    /// the balance of the bridge and with the supply on the other chains.
+    pub fn get_reserves(&self, token: Option<AccountId>) -> Reserves {
+        let token = match token {
+            Some(token) => token,
+            None => return Reserves {
+                token: None,
+                token_type: TokenType::Native,
+                chain: self.chain.clone(),
+                locked: U128(self.locked.native),
+                seeded: self.locked.is_seeded(&None),
+            },
+        };
+
+        let info = self.tokens.get_token(&token).unwrap_or_else(|| env::panic_str("Tokens: token is not registered"));
+        require!(!info.is_wrapped(), "Reserves: wrapped tokens are not locked");
+
+        let locked = match info.token_type {
+            TokenType::FT => self.locked.get_ft(&token),
+            TokenType::NFT => Balance::from(self.locked.get_nft_count(&token)),
+            TokenType::Native => 0,
+        };
+
+        Reserves {
+            seeded: self.locked.is_seeded(&Some(token.clone())),
+            token: Some(token),
+            token_type: info.token_type,
+            chain: info.chain,
+            locked: U128(locked),
+        }
+    }
+
+    /// Seeds the reserves a migrated bridge locked before the ledger existed, `token` is `None` for
+    /// native NEAR. `amount` sets the locked balance of native NEAR or a fungible token and the
+    /// supply of a wrapped token, `token_ids` are the locked NFTs. Both must include the deposits
+    /// and withdrawals made since the upgrade. Every token is seeded once.
+    pub fn seed_reserves(
+        &mut self,
+        token: Option<AccountId>,
+        amount: Option<U128>,
+        token_ids: Option<Vec<TokenId>>,
+        signatures: Vec<RecoverableSignature>,
+    ) {
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::SeedReserves)
+                .borsh(&token)
+                .borsh(&amount.map(|amount| amount.0))
+                .borsh(&token_ids)
+        );
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        require!(!self.locked.is_seeded(&token), "Reserves: token is already seeded");
+
+        let info = token.as_ref()
+            .map(|token| self.tokens.get_token(token).unwrap_or_else(|| env::panic_str("Tokens: token is not registered")));
+
+        match (info, amount, token_ids) {
+            (None, Some(amount), None) => self.locked.native = amount.0,
+            (Some(info), Some(amount), None) if info.is_wrapped() => {
+                self.wrapped_supply.supply.insert(&info.token, &amount.0);
+            }
+            (Some(info), Some(amount), None) if info.token_type == TokenType::FT => {
+                self.locked.ft.insert(&info.token, &amount.0);
+            }
+            (Some(info), None, Some(token_ids)) if info.token_type == TokenType::NFT && !info.is_wrapped() => {
+                token_ids.iter().for_each(|token_id| self.locked.lock_nft(&info.token, token_id));
+            }
+            _ => env::panic_str("Reserves: amount or token ids do not match the token type"),
+        }
+
+        self.locked.seeded.insert(&token);
+    }
+
+    pub fn is_nft_locked(&self, token: AccountId, token_id: TokenId) -> bool {
+        self.locked.is_nft_locked(&token, &token_id)
+    }
+}
+
+impl Bridge {
+    /// Takes the withdrawn locked assets from the ledger before the delivery, panics if the ledger
+    /// does not hold them. Wrapped assets are minted instead.
+    pub(crate) fn internal_unlock_withdrawal(&mut self, withdrawal: &Withdrawal) {
+        match withdrawal.kind.clone() {
+            WithdrawalKind::Native { amount } => self.locked.unlock_native(amount.0),
+            WithdrawalKind::FT { token, amount, .. } if !self.tokens.is_wrapped(&token) => {
+                self.locked.unlock_ft(&token, amount.0);
+                self.locked.send_ft(&token, amount.0);
+            }
+            WithdrawalKind::NFT { token, token_id, .. } if !self.tokens.is_wrapped(&token) => {
+                self.locked.unlock_nft(&token, &token_id);
+                self.locked.send_nft(&token, &token_id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Takes the withdrawn locked assets out of flight once the delivery resolves.
+    pub(crate) fn internal_settle_withdrawal(&mut self, withdrawal: &Withdrawal) {
+        match withdrawal.kind.clone() {
+            WithdrawalKind::FT { token, amount, .. } if !self.tokens.is_wrapped(&token) => {
+                self.locked.settle_ft(&token, amount.0);
+            }
+            WithdrawalKind::NFT { token, token_id, .. } if !self.tokens.is_wrapped(&token) => {
+                self.locked.settle_nft(&token, &token_id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Gives the assets of a failed delivery back to the ledger, they stay on the bridge.
+    pub(crate) fn internal_relock_withdrawal(&mut self, withdrawal: &Withdrawal) {
+        match withdrawal.kind.clone() {
+            WithdrawalKind::Native { amount } => self.locked.lock_native(amount.0),
+            WithdrawalKind::FT { token, amount, .. } if !self.tokens.is_wrapped(&token) => {
+                self.locked.lock_ft(&token, amount.0);
+            }
+            WithdrawalKind::NFT { token, token_id, .. } if !self.tokens.is_wrapped(&token) => {
+                self.locked.lock_nft(&token, &token_id);
+            }
+            _ => {}
+        }
+    }
+}
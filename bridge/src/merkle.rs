@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use shared::{Data, to_32_bytes, u128_to_bytes, usize_to_bytes, vector_from_32_bytes};
 use near_contract_standards::non_fungible_token::TokenId;
 use near_sdk::{AccountId, Balance};
@@ -12,10 +14,15 @@ pub struct TransferFullMetaOperation {
     pub title: Option<String>,
     pub media_url: Option<String>,
     pub media_hash: Option<String>,
+    // Appended with a presence byte if the message or the royalty is set, so transfers without
+    // both keep the previous encoding
+    pub msg: Option<String>,
+    // Royalty shares of the NFT in basis points, appended only if present after the message
+    pub royalty: Option<HashMap<AccountId, u32>>,
 }
 
 impl TransferFullMetaOperation {
-    pub fn new_ft_transfer(token: AccountId, amount: U128) -> Self {
+    pub fn new_ft_transfer(token: AccountId, amount: U128, msg: Option<String>) -> Self {
         TransferFullMetaOperation {
             token: Some(token),
             token_id_to: None,
@@ -23,10 +30,20 @@ impl TransferFullMetaOperation {
             title: None,
             media_url: None,
             media_hash: None,
+            msg,
+            royalty: None,
         }
     }
 
-    pub fn new_nft_transfer(token_id: TokenId, token: Option<AccountId>, title: String, media_url: String, media_hash: String) -> Self {
+    pub fn new_nft_transfer(
+        token_id: TokenId,
+        token: Option<AccountId>,
+        title: String,
+        media_url: String,
+        media_hash: String,
+        msg: Option<String>,
+        royalty: Option<HashMap<AccountId, u32>>,
+    ) -> Self {
         TransferFullMetaOperation {
             token,
             token_id_to: Some(token_id),
@@ -34,6 +51,8 @@ impl TransferFullMetaOperation {
             amount: None,
             media_url: Some(media_url),
             media_hash: Some(media_hash),
+            msg,
+            royalty,
         }
     }
 }
@@ -92,6 +111,30 @@ impl Data for TransferFullMetaOperation {
             data.append(&mut zero_length.clone());
         }
 
+        // The presence byte tells a missing message from an empty one
+        if let Some(msg) = self.msg.clone() {
+            let msg_bytes = &mut Vec::from(msg.into_bytes());
+            data.push(1);
+            data.append(&mut vector_from_32_bytes(usize_to_bytes(msg_bytes.clone().len())));
+            data.append(msg_bytes);
+        } else if self.royalty.is_some() {
+            data.push(0);
+        }
+
+        // Accounts are sorted, so the encoding does not depend on the map order
+        if let Some(royalty) = self.royalty.clone() {
+            let mut royalty: Vec<(AccountId, u32)> = royalty.into_iter().collect();
+            royalty.sort();
+
+            data.append(&mut vector_from_32_bytes(usize_to_bytes(royalty.len())));
+            for (account, share) in royalty {
+                let account_bytes = &mut Vec::from(account.as_bytes());
+                data.append(&mut vector_from_32_bytes(usize_to_bytes(account_bytes.clone().len())));
+                data.append(account_bytes);
+                data.append(&mut vector_from_32_bytes(usize_to_bytes(share as usize)));
+            }
+        }
+
         data
     }
 }
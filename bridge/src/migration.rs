@@ -0,0 +1,260 @@
+use near_contract_standards::non_fungible_token::{Token, TokenId};
+use near_sdk::{AccountId, Balance, env, Gas, log, near_bindgen, Promise, PromiseError, require};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LazyOption;
+use near_sdk::json_types::{U128, U64};
+use serde::{Deserialize, Serialize};
+
+use shared::{AuthorizedOperation, GAS_FOR_TX, OperationType, RecoverableSignature, TokenType, TRANSFER_DEPOSIT};
+
+use crate::events_migration::{AssetsMigratedData, MigrationData};
+use crate::timelock::NANOS_IN_SECOND;
+
+use super::*;
+
+/// Delay between the announcement and the migration, in seconds.
+const DEFAULT_MIGRATION_DELAY: u64 = 7 * 24 * 60 * 60;
+/// Native balance kept on the bridge to pay the deposits of the token transfers.
+const MIGRATION_NATIVE_RESERVE: Balance = 100_000_000_000_000_000_000_000; // 0.1 NEAR
+/// Deposit of the successor registration on a fungible token, the unused part is refunded by the token.
+const MIGRATION_STORAGE_DEPOSIT: Balance = 12_500_000_000_000_000_000_000; // 0.0125 NEAR
+/// Maximal number of NFTs of one token moved by a single migration call.
+const MIGRATION_NFT_LIMIT: u64 = 10;
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub struct MigrationAnnouncement {
+    pub successor: AccountId,
+    pub announced_at: U64,
+    pub executable_at: U64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MigrationManager {
+    pub announcement: LazyOption<MigrationAnnouncement>,
+    // Seconds
+    pub delay: u64,
+}
+
+impl MigrationManager {
+    pub fn new() -> Self {
+        Self {
+            announcement: LazyOption::new(b"migration".to_vec(), None),
+            delay: DEFAULT_MIGRATION_DELAY,
+        }
+    }
+
+    pub fn announce(&mut self, successor: AccountId) -> MigrationAnnouncement {
+        require!(successor != env::current_account_id(), "Migration: successor can not be the bridge");
+
+        let announcement = MigrationAnnouncement {
+            successor,
+            announced_at: U64(env::block_timestamp()),
+            executable_at: U64(env::block_timestamp() + self.delay * NANOS_IN_SECOND),
+        };
+        self.announcement.set(&announcement);
+
+        announcement
+    }
+
+    pub fn cancel(&mut self) -> MigrationAnnouncement {
+        self.announcement.take().unwrap_or_else(|| env::panic_str("Migration: migration is not announced"))
+    }
+
+    /// Panics if the migration to the successor is not announced or its delay has not passed.
+    pub fn assert_executable(&self, successor: &AccountId) {
+        let announcement = self.announcement.get().unwrap_or_else(|| env::panic_str("Migration: migration is not announced"));
+
+        require!(&announcement.successor == successor, "Migration: successor was not announced");
+        require!(env::block_timestamp() >= announcement.executable_at.0, "Migration: delay has not passed");
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    pub fn get_migration(&self) -> Option<MigrationAnnouncement> {
+        self.migration.announcement.get()
+    }
+
+    /// Returns the delay between the announcement and the migration, in seconds.
+    pub fn get_migration_delay(&self) -> U64 {
+        U64(self.migration.delay)
+    }
+
+    pub fn set_migration_delay(&mut self, delay: U64, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::SetMigrationDelay).number(delay.0 as u128));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.migration.delay = delay.0;
+    }
+
+    /// Announces the migration of the locked assets to the successor bridge, it can be executed
+    /// after the migration delay. A new announcement replaces the previous one.
+    pub fn announce_migration(&mut self, successor: AccountId, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::AnnounceMigration).account(&successor));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        let announcement = self.migration.announce(successor);
+        let executable_at = announcement.executable_at.0.to_string();
+        NearEvent::migration_announced(vec![MigrationData::new(&announcement.successor, Some(&executable_at))]).emit();
+    }
+
+    pub fn cancel_migration(&mut self, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::CancelMigration));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        let announcement = self.migration.cancel();
+        NearEvent::migration_cancelled(vec![MigrationData::new(&announcement.successor, None)]).emit();
+    }
+
+    /// Moves the native balance and the locked tokens to the announced successor. `None` tokens
+    /// stand for all registered locked tokens. NFTs are moved in batches, so the call may have to
+    /// be repeated until the bridge owns none of them. Can be executed only while the bridge is
+    /// paused.
+    pub fn migrate_assets(
+        &mut self,
+        successor: AccountId,
+        tokens: Option<Vec<AccountId>>,
+        signatures: Vec<RecoverableSignature>,
+    ) {
+        Bridge::require_paused();
+
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::MigrateAssets).account(&successor).borsh(&tokens)
+        );
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.migration.assert_executable(&successor);
+
+        let tokens = match tokens {
+            Some(tokens) => tokens.iter()
+                .map(|token| self.tokens.get_token(token).unwrap_or_else(|| env::panic_str("Tokens: token is not registered")))
+                .collect(),
+            None => self.tokens.get_tokens(),
+        };
+
+        self.internal_migrate_native(&successor);
+
+        for info in tokens.into_iter().filter(|info| !info.is_wrapped()) {
+            match info.token_type {
+                TokenType::FT => {
+                    ext_fungible_token::ext(info.token.clone())
+                        .with_static_gas(GAS_FOR_TX)
+                        .ft_balance_of(env::current_account_id())
+                        .then(
+                            Self::ext(env::current_account_id())
+                                .with_static_gas(Gas(GAS_FOR_TX.0 * 4))
+                                .migrate_ft_callback(info.token, successor.clone())
+                        );
+                }
+                TokenType::NFT => {
+                    ext_non_fungible_token::ext(info.token.clone())
+                        .with_static_gas(GAS_FOR_TX)
+                        .nft_tokens_for_owner(env::current_account_id(), None, Some(MIGRATION_NFT_LIMIT))
+                        .then(
+                            Self::ext(env::current_account_id())
+                                .with_static_gas(Gas(GAS_FOR_TX.0 * (2 * MIGRATION_NFT_LIMIT + 1)))
+                                .migrate_nft_callback(info.token, successor.clone())
+                        );
+                }
+                TokenType::Native => {}
+            }
+        }
+    }
+
+    /// Registers the successor on the token and transfers the whole balance to it, the locked
+    /// balance is cleared once the transfer succeeds.
+    #[private]
+    pub fn migrate_ft_callback(
+        &mut self,
+        #[callback_result] balance: Result<U128, PromiseError>,
+        token: AccountId,
+        successor: AccountId,
+    ) {
+        let balance = balance.unwrap_or_else(|_| env::panic_str("Migration: failed to get token balance"));
+        if balance.0 == 0 {
+            return;
+        }
+
+        ext_fungible_token::ext(token.clone())
+            .with_static_gas(GAS_FOR_TX)
+            .with_attached_deposit(MIGRATION_STORAGE_DEPOSIT)
+            .storage_deposit(Some(successor.clone()), Some(true))
+            .then(
+                ext_fungible_token::ext(token.clone())
+                    .with_static_gas(GAS_FOR_TX)
+                    .with_attached_deposit(TRANSFER_DEPOSIT)
+                    .ft_transfer(successor.clone(), balance, None)
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TX)
+                    .migrate_ft_resolve(token, successor, balance)
+            );
+    }
+
+    #[private]
+    pub fn migrate_ft_resolve(&mut self, token: AccountId, successor: AccountId, amount: U128) {
+        if !is_promise_succeeded(0) {
+            log!("Migration: transfer of {} to {} failed, the balance stays locked", token, successor);
+            return;
+        }
+
+        self.locked.clear_ft(&token);
+
+        let amount = amount.0.to_string();
+        NearEvent::assets_migrated(vec![AssetsMigratedData::new(&successor, Some(token.as_str()), None, Some(&amount))]).emit();
+    }
+
+    /// Transfers a batch of the owned NFTs to the successor, every token is unlocked once its
+    /// transfer succeeds.
+    #[private]
+    pub fn migrate_nft_callback(
+        &mut self,
+        #[callback_result] owned: Result<Vec<Token>, PromiseError>,
+        token: AccountId,
+        successor: AccountId,
+    ) {
+        let owned = owned.unwrap_or_else(|_| env::panic_str("Migration: failed to get owned tokens"));
+
+        for item in owned {
+            self.internal_nft_transfer(token.clone(), item.token_id.clone(), successor.clone(), TRANSFER_DEPOSIT)
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_TX)
+                        .migrate_nft_resolve(token.clone(), item.token_id, successor.clone())
+                );
+        }
+    }
+
+    #[private]
+    pub fn migrate_nft_resolve(&mut self, token: AccountId, token_id: TokenId, successor: AccountId) {
+        if !is_promise_succeeded(0) {
+            log!("Migration: transfer of {} {} to {} failed, the token stays locked", token, token_id, successor);
+            return;
+        }
+
+        self.locked.clear_nft(&token, &token_id);
+
+        NearEvent::assets_migrated(vec![AssetsMigratedData::new(&successor, Some(token.as_str()), Some(token_id.as_str()), None)]).emit();
+    }
+}
+
+impl Bridge {
+    /// Transfers the locked native balance, limited by the balance which is not used for the
+    /// storage and the reserve.
+    fn internal_migrate_native(&mut self, successor: &AccountId) {
+        let storage_cost = Balance::from(env::storage_usage()) * env::storage_byte_cost();
+        let available = env::account_balance().saturating_sub(storage_cost + MIGRATION_NATIVE_RESERVE);
+        let amount = std::cmp::min(self.locked.native, available);
+        if amount == 0 {
+            return;
+        }
+
+        Promise::new(successor.clone()).transfer(amount);
+        self.locked.native = self.locked.native.saturating_sub(amount);
+
+        let amount = amount.to_string();
+        NearEvent::assets_migrated(vec![AssetsMigratedData::new(successor, None, None, Some(&amount))]).emit();
+    }
+}
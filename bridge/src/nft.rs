@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
+use near_sdk::AccountId;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::json_types::Base64VecU8;
 use near_sdk::serde::{Deserialize, Serialize};
@@ -9,4 +13,37 @@ pub struct NftMetadata {
     pub description: Option<String>, // free-form description
     pub media: Option<String>, // URL to associated media, preferably to decentralized, content-addressed storage
     pub media_hash: Option<Base64VecU8>, // Base64-encoded sha256 hash of content referenced by the `media` field. Required if `media` is included.
+    #[serde(default)]
+    pub royalty: Option<HashMap<AccountId, u32>>, // Royalty shares from the origin chain in basis points, ex. ERC-2981 of the EVM collections
+}
+
+impl NftMetadata {
+    pub fn to_token_metadata(&self) -> TokenMetadata {
+        TokenMetadata {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            media: self.media.clone(),
+            media_hash: self.media_hash.clone(),
+            copies: Some(1),
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+}
+
+impl From<TokenMetadata> for NftMetadata {
+    fn from(metadata: TokenMetadata) -> Self {
+        Self {
+            title: metadata.title,
+            description: metadata.description,
+            media: metadata.media,
+            media_hash: metadata.media_hash,
+            royalty: None,
+        }
+    }
 }
@@ -0,0 +1,100 @@
+use near_sdk::{AccountId, env, near_bindgen};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedSet;
+use serde::{Deserialize, Serialize};
+
+use shared::{AuthorizedOperation, OperationType, RecoverableSignature};
+
+use crate::guardians::GuardianActionKind;
+
+use super::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub enum PauseScope {
+    /// All deposits: `nft_on_transfer`, `ft_on_transfer` and `native_deposit`.
+    Deposits,
+    /// All withdrawals, including retries and queued claims.
+    Withdrawals,
+    /// Deposits and withdrawals of a single token.
+    Token {
+        token: AccountId,
+    },
+    /// Deposits to a single destination chain.
+    Chain {
+        chain: String,
+    },
+}
+
+/// Scoped pause flags, checked in addition to the global pause.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PauseScopes {
+    pub scopes: UnorderedSet<PauseScope>,
+}
+
+impl PauseScopes {
+    pub fn new() -> Self {
+        Self {
+            scopes: UnorderedSet::new(b"pause_scopes".to_vec()),
+        }
+    }
+
+    pub fn pause(&mut self, scope: &PauseScope) {
+        assert!(self.scopes.insert(scope), "PauseScopes: scope is already paused");
+    }
+
+    pub fn resume(&mut self, scope: &PauseScope) {
+        assert!(self.scopes.remove(scope), "PauseScopes: scope is not paused");
+    }
+
+    pub fn get_scopes(&self) -> Vec<PauseScope> {
+        self.scopes.to_vec()
+    }
+
+    pub fn require_deposit_unpaused(&self, token: Option<AccountId>, chain: String) {
+        self.require_unpaused(&PauseScope::Deposits);
+        self.require_unpaused(&PauseScope::Chain { chain });
+        if let Some(token) = token {
+            self.require_unpaused(&PauseScope::Token { token });
+        }
+    }
+
+    pub fn require_withdrawal_unpaused(&self, token: Option<AccountId>) {
+        self.require_unpaused(&PauseScope::Withdrawals);
+        if let Some(token) = token {
+            self.require_unpaused(&PauseScope::Token { token });
+        }
+    }
+
+    fn require_unpaused(&self, scope: &PauseScope) {
+        if self.scopes.contains(scope) {
+            env::panic_str(format!("PauseScopes: {:?} is paused", scope).as_str());
+        }
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    pub fn get_paused_scopes(&self) -> Vec<PauseScope> {
+        self.pause_scopes.get_scopes()
+    }
+
+    pub fn pause_scope(&mut self, scope: PauseScope, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::PauseScope).borsh(&scope));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.pause_scopes.pause(&scope);
+    }
+
+    /// Pauses the scope without the signers, can be called only by guardians.
+    pub fn guardian_pause_scope(&mut self, scope: PauseScope) {
+        self.guardians.record_action(&env::predecessor_account_id(), GuardianActionKind::PauseScope { scope: scope.clone() });
+        self.pause_scopes.pause(&scope);
+    }
+
+    pub fn resume_scope(&mut self, scope: PauseScope, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::ResumeScope).borsh(&scope));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.pause_scopes.resume(&scope);
+    }
+}
@@ -0,0 +1,120 @@
+use near_sdk::{env, near_bindgen, require};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U64;
+use serde::{Deserialize, Serialize};
+
+use shared::Hash;
+
+use super::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub enum WithdrawalStatus {
+    /// A retry of the withdrawal is in flight.
+    Pending,
+    /// The last delivery attempt failed, the withdrawal can be retried.
+    Failed,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub struct PendingWithdrawal {
+    pub withdrawal: Withdrawal,
+    // Root the withdrawal was verified against
+    pub root: String,
+    pub status: WithdrawalStatus,
+    pub attempts: u32,
+    pub updated_at: U64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PendingWithdrawals {
+    pub withdrawals: UnorderedMap<String, PendingWithdrawal>,
+}
+
+impl PendingWithdrawals {
+    pub fn new() -> Self {
+        Self {
+            withdrawals: UnorderedMap::new(b"pending_withdrawals".to_vec()),
+        }
+    }
+
+    pub fn mark_failed(&mut self, withdrawal: &Withdrawal, root: Hash) {
+        let attempts = self.withdrawals.get(&withdrawal.origin).map_or(0, |pending| pending.attempts);
+
+        self.withdrawals.insert(&withdrawal.origin.clone(), &PendingWithdrawal {
+            withdrawal: withdrawal.clone(),
+            root: root.as_str(),
+            status: WithdrawalStatus::Failed,
+            attempts: attempts + 1,
+            updated_at: U64(env::block_timestamp()),
+        });
+    }
+
+    /// Moves the failed withdrawal to the pending status and returns it.
+    pub fn start_retry(&mut self, origin: &String) -> PendingWithdrawal {
+        let mut pending = self.withdrawals.get(origin)
+            .unwrap_or_else(|| env::panic_str("PendingWithdrawals: withdrawal not found"));
+        assert_eq!(pending.status, WithdrawalStatus::Failed, "PendingWithdrawals: withdrawal is already pending");
+
+        pending.status = WithdrawalStatus::Pending;
+        pending.updated_at = U64(env::block_timestamp());
+        self.withdrawals.insert(origin, &pending);
+
+        pending
+    }
+
+    pub fn remove(&mut self, origin: &String) {
+        self.withdrawals.remove(origin);
+    }
+
+    pub fn get(&self, origin: &String) -> Option<PendingWithdrawal> {
+        self.withdrawals.get(origin)
+    }
+
+    pub fn list(&self, status: WithdrawalStatus, from_index: Option<u64>, limit: Option<u64>) -> Vec<PendingWithdrawal> {
+        self.withdrawals.values()
+            .filter(|pending| pending.status == status)
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u64::MAX) as usize)
+            .collect()
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    /// Re-executes a failed withdrawal with the already verified payload. Can be called by anyone
+    /// with at least the deposit required by the withdrawal, the unused part is refunded. The retry
+    /// goes through the limits and the timelock like the first attempt, a queued retry leaves the
+    /// pending withdrawals until it is claimed.
+    #[payable]
+    pub fn retry_withdrawal(&mut self, origin: String) {
+        Bridge::require_unpaused();
+
+        let pending = self.pending.start_retry(&origin);
+        let deposit = self.internal_withdrawal_deposit(&pending.withdrawal);
+        require!(
+            env::attached_deposit() >= deposit,
+            format!("Requires attached deposit of at least {} yoctoNEAR", deposit),
+        );
+
+        let root = Hash::from_hex(pending.root);
+        if !self.internal_check_withdrawal_delay(&pending.withdrawal, root.clone(), env::attached_deposit()) {
+            self.pending.remove(&origin);
+            return;
+        }
+
+        self.internal_withdraw(pending.withdrawal, root, env::attached_deposit(), env::predecessor_account_id());
+    }
+
+    pub fn get_pending_withdrawal(&self, origin: String) -> Option<PendingWithdrawal> {
+        self.pending.get(&origin)
+    }
+
+    pub fn get_pending_withdrawals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<PendingWithdrawal> {
+        self.pending.list(WithdrawalStatus::Pending, from_index, limit)
+    }
+
+    pub fn get_failed_withdrawals(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<PendingWithdrawal> {
+        self.pending.list(WithdrawalStatus::Failed, from_index, limit)
+    }
+}
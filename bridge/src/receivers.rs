@@ -3,7 +3,7 @@ use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
 use near_sdk::{AccountId, env, PromiseOrValue};
 use near_sdk::json_types::U128;
 
-use shared::{BURN_ADDRESS, TRANSFER_DEPOSIT, TransferLog};
+use shared::{TokenType, TransferLog};
 
 use super::*;
 
@@ -22,25 +22,38 @@ impl NonFungibleTokenReceiver for Bridge {
         assert_eq!(sender_id.clone(), self.fee_contract.clone(), "Sender must be fee contract");
 
         let log: TransferLog = serde_json::from_str(&msg).unwrap();
+        let token = self.tokens.get_registered_token(&env::predecessor_account_id(), TokenType::NFT);
+        self.pause_scopes.require_deposit_unpaused(Some(token.token.clone()), log.chain_to.clone());
+        let receiver = self.internal_normalize_receiver(&log.chain_to, &log.receiver);
 
-        if log.is_wrapped {
-            self.internal_nft_transfer(
-                env::predecessor_account_id(),
-                token_id.clone(),
-                AccountId::new_unchecked(BURN_ADDRESS.to_string()),
-                Balance::from(TRANSFER_DEPOSIT),
-            );
+        if token.is_wrapped() {
+            self.internal_nft_burn(env::predecessor_account_id(), token_id.clone());
+            self.wrapped_supply.burn(&token.token, 1);
+        } else {
+            self.locked.lock_nft(&token.token, &token_id);
         }
 
+        let deposit_nonce = self.internal_commit_deposit(
+            Some(token.token.clone()),
+            Some(token_id.clone()),
+            None,
+            log.sender.clone(),
+            receiver.clone(),
+            log.chain_to.clone(),
+            log.bundle_data.clone(),
+            log.bundle_salt.clone(),
+        );
+
         NearEvent::nft_deposited(vec![NftDepositedData::new(
             &env::predecessor_account_id(),
             &token_id.clone(),
             &log.sender.clone(),
-            &log.receiver.clone(),
+            &receiver.clone(),
             log.chain_to.as_str(),
-            log.is_wrapped,
+            token.is_wrapped(),
             log.bundle_data.clone(),
             log.bundle_salt.clone(),
+            deposit_nonce,
         )]).emit();
 
         PromiseOrValue::Value(false)
@@ -61,25 +74,38 @@ impl FungibleTokenReceiver for Bridge {
         assert_eq!(sender_id.clone(), self.fee_contract.clone(), "Sender must be fee contract");
 
         let log: TransferLog = serde_json::from_str(&msg).unwrap();
+        let token = self.tokens.get_registered_token(&env::predecessor_account_id(), TokenType::FT);
+        self.pause_scopes.require_deposit_unpaused(Some(token.token.clone()), log.chain_to.clone());
+        let receiver = self.internal_normalize_receiver(&log.chain_to, &log.receiver);
 
-        if log.is_wrapped {
-            self.internal_ft_transfer(
-                env::predecessor_account_id(),
-                amount,
-                AccountId::new_unchecked(BURN_ADDRESS.to_string()),
-                Balance::from(TRANSFER_DEPOSIT),
-            );
+        if token.is_wrapped() {
+            self.internal_ft_burn(env::predecessor_account_id(), amount);
+            self.wrapped_supply.burn(&token.token, amount.0);
+        } else {
+            self.locked.lock_ft(&token.token, amount.0);
         }
 
+        let deposit_nonce = self.internal_commit_deposit(
+            Some(token.token.clone()),
+            None,
+            Some(amount),
+            log.sender.clone(),
+            receiver.clone(),
+            log.chain_to.clone(),
+            log.bundle_data.clone(),
+            log.bundle_salt.clone(),
+        );
+
         NearEvent::ft_deposited(vec![FtDepositedData::new(
             &env::predecessor_account_id(),
             amount,
             &log.sender.clone(),
-            &log.receiver.clone(),
+            &receiver.clone(),
             log.chain_to.as_str(),
-            log.is_wrapped,
+            token.is_wrapped(),
             log.bundle_data.clone(),
             log.bundle_salt.clone(),
+            deposit_nonce,
         )]).emit();
 
         PromiseOrValue::Value(U128(0))
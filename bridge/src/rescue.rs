@@ -0,0 +1,106 @@
+use near_contract_standards::non_fungible_token::{Token, TokenId};
+use near_sdk::{AccountId, env, near_bindgen, PromiseError, require};
+use near_sdk::json_types::U128;
+
+use shared::{AuthorizedOperation, GAS_FOR_TX, OperationType, RecoverableSignature, TRANSFER_DEPOSIT};
+
+use crate::events_rescue::TokensRescuedData;
+
+use super::*;
+
+#[near_bindgen]
+impl Bridge {
+    /// Returns the tokens sent to the bridge with a plain `ft_transfer` / `nft_transfer`. Either
+    /// `amount` of the fungible token or `token_id` of the non-fungible token is rescued, the
+    /// locked balance which backs the bridged supply and the withdrawals in flight can not be touched. Wrapped tokens minted
+    /// by the bridge and tokens with unseeded reserves are never rescued.
+    pub fn rescue_tokens(
+        &mut self,
+        token: AccountId,
+        amount: Option<U128>,
+        token_id: Option<TokenId>,
+        receiver: AccountId,
+        signatures: Vec<RecoverableSignature>,
+    ) {
+        require!(!self.tokens.is_wrapped(&token), "Rescue: wrapped tokens can not be rescued");
+        require!(self.locked.is_seeded(&Some(token.clone())), "Rescue: reserves of the token are not seeded");
+
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::RescueTokens)
+                .account(&token)
+                .borsh(&amount)
+                .borsh(&token_id)
+                .account(&receiver)
+        );
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        match (amount, token_id) {
+            (Some(amount), None) => {
+                require!(amount.0 > 0, "Rescue: amount must be positive");
+
+                ext_fungible_token::ext(token.clone())
+                    .with_static_gas(GAS_FOR_TX)
+                    .ft_balance_of(env::current_account_id())
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(GAS_FOR_TX + GAS_FOR_TX)
+                            .rescue_ft_callback(token, amount, receiver)
+                    );
+            }
+            (None, Some(token_id)) => {
+                ext_non_fungible_token::ext(token.clone())
+                    .with_static_gas(GAS_FOR_TX)
+                    .nft_token(token_id.clone())
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(GAS_FOR_TX + GAS_FOR_TX)
+                            .rescue_nft_callback(token, token_id, receiver)
+                    );
+            }
+            _ => env::panic_str("Rescue: either amount or token id is required"),
+        }
+    }
+
+    #[private]
+    pub fn rescue_ft_callback(
+        &mut self,
+        #[callback_result] balance: Result<U128, PromiseError>,
+        token: AccountId,
+        amount: U128,
+        receiver: AccountId,
+    ) {
+        let balance = balance.unwrap_or_else(|_| env::panic_str("Rescue: failed to get token balance"));
+        let reserved = self.locked.get_ft(&token) + self.locked.get_ft_in_flight(&token);
+        let excess = balance.0.saturating_sub(reserved);
+        require!(amount.0 <= excess, format!("Rescue: only {} tokens are not locked", excess));
+
+        ext_fungible_token::ext(token.clone())
+            .with_static_gas(GAS_FOR_TX)
+            .with_attached_deposit(TRANSFER_DEPOSIT)
+            .ft_transfer(receiver.clone(), amount, None);
+
+        let amount = amount.0.to_string();
+        NearEvent::tokens_rescued(vec![TokensRescuedData::new(&token, None, Some(&amount), &receiver)]).emit();
+    }
+
+    #[private]
+    pub fn rescue_nft_callback(
+        &mut self,
+        #[callback_result] owned: Result<Option<Token>, PromiseError>,
+        token: AccountId,
+        token_id: TokenId,
+        receiver: AccountId,
+    ) {
+        let owned = owned.unwrap_or_else(|_| env::panic_str("Rescue: failed to get token"));
+        require!(
+            owned.map_or(false, |item| item.owner_id == env::current_account_id()),
+            "Rescue: token is not owned by the bridge",
+        );
+        require!(!self.locked.is_nft_locked(&token, &token_id), "Rescue: token is locked");
+        require!(!self.locked.is_nft_in_flight(&token, &token_id), "Rescue: token is being withdrawn");
+
+        self.internal_nft_transfer(token.clone(), token_id.clone(), receiver.clone(), TRANSFER_DEPOSIT);
+
+        NearEvent::tokens_rescued(vec![TokensRescuedData::new(&token, Some(token_id.as_str()), None, &receiver)]).emit();
+    }
+}
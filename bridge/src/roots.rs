@@ -0,0 +1,36 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::env;
+
+use shared::Hash;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Roots {
+    /// Signed Merkle roots mapped to the block timestamp they were submitted at.
+    pub roots: LookupMap<String, u64>,
+}
+
+impl Roots {
+    pub fn new() -> Self {
+        Self {
+            roots: LookupMap::new(b"roots".to_vec()),
+        }
+    }
+
+    pub fn add_root(&mut self, root: Hash) {
+        if self.roots.contains_key(&root.as_str()) {
+            env::panic_str("Roots: root already submitted");
+        }
+        self.roots.insert(&root.as_str(), &env::block_timestamp());
+    }
+
+    pub fn get_root(&self, root: Hash) -> Option<u64> {
+        self.roots.get(&root.as_str())
+    }
+
+    pub fn check_root(&self, root: Hash) {
+        if !self.roots.contains_key(&root.as_str()) {
+            env::panic_str("Roots: root is not submitted");
+        }
+    }
+}
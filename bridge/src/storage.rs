@@ -0,0 +1,108 @@
+use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds};
+use near_sdk::{AccountId, Balance, env, log, near_bindgen, Promise, PromiseError};
+use near_sdk::json_types::U128;
+
+use shared::{GAS_FOR_TRANSFER_CALL, GAS_FOR_TX, TRANSFER_DEPOSIT};
+
+use super::*;
+
+#[near_bindgen]
+impl Bridge {
+    /// Registers the account on the token with NEP-145 `storage_deposit` if it is not registered
+    /// yet and delivers the withdrawn tokens. The registration is paid from the withdrawal deposit,
+    /// the unused part is refunded to the sender. If the deposit does not cover the registration or
+    /// the registration can not be checked, it is refunded and the delivery is tried without it,
+    /// a failed delivery keeps the withdrawal pending.
+    #[private]
+    pub fn ft_storage_callback(
+        &mut self,
+        #[callback_result] balance: Result<Option<StorageBalance>, PromiseError>,
+        #[callback_result] bounds: Result<StorageBalanceBounds, PromiseError>,
+        withdrawal: Withdrawal,
+        account_id: AccountId,
+        deposit: U128,
+        sender: AccountId,
+    ) -> Promise {
+        // The origin is already used, so the storage checks must not panic either
+        let registration: Balance = match (balance, bounds) {
+            (Ok(Some(_)), _) => 0,
+            (Ok(None), Ok(bounds)) => bounds.min.0,
+            _ => {
+                log!("Storage: failed to check the registration of {}, it is not paid", account_id);
+                0
+            }
+        };
+
+        let (token, amount, msg) = match withdrawal.kind {
+            WithdrawalKind::FT { token, amount, msg } => (token, amount, msg),
+            _ => env::panic_str("Storage: not a fungible token withdrawal"),
+        };
+
+        let is_wrapped = self.tokens.is_wrapped(&token);
+        let transfer_deposit = if is_wrapped { 0 } else { TRANSFER_DEPOSIT };
+
+        // The origin is already used, so an unpaid registration must not panic. The delivery to the
+        // unregistered account fails instead and the withdrawal is kept pending for a retry with
+        // a larger deposit.
+        let registration = if deposit.0 >= registration + transfer_deposit {
+            registration
+        } else {
+            log!("Storage: registration of {} requires {} yoctoNEAR, the withdrawal is kept pending", account_id, registration);
+            0
+        };
+
+        let refund = deposit.0.saturating_sub(registration + transfer_deposit);
+        if refund > 0 {
+            Promise::new(sender.clone()).transfer(refund);
+        }
+
+        let token_contract = ext_fungible_token::ext(token.clone()).with_attached_deposit(transfer_deposit);
+        let delivery = match (is_wrapped, msg) {
+            (true, _) => token_contract.with_static_gas(GAS_FOR_TX).ft_mint(account_id.clone(), amount, Some(sender)),
+            (false, Some(msg)) => token_contract.with_static_gas(GAS_FOR_TRANSFER_CALL).ft_transfer_call(account_id.clone(), amount, None, msg),
+            (false, None) => token_contract.with_static_gas(GAS_FOR_TX).ft_transfer(account_id.clone(), amount, None),
+        };
+
+        if registration == 0 {
+            return delivery;
+        }
+
+        ext_fungible_token::ext(token)
+            .with_static_gas(GAS_FOR_TX)
+            .with_attached_deposit(registration)
+            .storage_deposit(Some(account_id), Some(true))
+            .then(delivery)
+    }
+}
+
+impl Bridge {
+    /// Checks the NEP-145 storage registration of the account before the delivery of fungible
+    /// tokens, see `ft_storage_callback`.
+    pub(crate) fn internal_ft_delivery_promise(
+        &self,
+        withdrawal: &Withdrawal,
+        token: AccountId,
+        account_id: AccountId,
+        deposit: Balance,
+        sender: &AccountId,
+    ) -> Promise {
+        let delivery_gas = match withdrawal.msg() {
+            Some(_) => GAS_FOR_TRANSFER_CALL,
+            None => GAS_FOR_TX,
+        };
+
+        ext_fungible_token::ext(token.clone())
+            .with_static_gas(GAS_FOR_TX)
+            .storage_balance_of(account_id.clone())
+            .and(
+                ext_fungible_token::ext(token)
+                    .with_static_gas(GAS_FOR_TX)
+                    .storage_balance_bounds()
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TX + GAS_FOR_TX + delivery_gas)
+                    .ft_storage_callback(withdrawal.clone(), account_id, U128(deposit), sender.clone())
+            )
+    }
+}
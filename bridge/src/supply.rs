@@ -0,0 +1,195 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, Balance, env, log, near_bindgen, require};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use serde::{Deserialize, Serialize};
+
+use shared::{AuthorizedOperation, BURN_ADDRESS, GAS_FOR_TX, OperationType, RecoverableSignature, TokenType, TRANSFER_DEPOSIT};
+
+use super::*;
+
+/// Supply of the wrapped tokens minted by the bridge: FT amounts and the number of NFTs. Withdrawals
+/// add to it when the mint starts and take back if it fails, deposits burn it.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct WrappedSupply {
+    pub supply: LookupMap<AccountId, Balance>,
+    pub caps: LookupMap<AccountId, Balance>,
+}
+
+/// Bridged supply of a wrapped token.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TokenSupply {
+    pub token: AccountId,
+    pub token_type: TokenType,
+    // Chain the token originates from
+    pub chain: String,
+    // Minted minus burned amount, the number of NFTs for non-fungible tokens
+    pub supply: U128,
+    pub mint_cap: Option<U128>,
+    // False until the supply minted before the upgrade is seeded with `seed_reserves`
+    pub seeded: bool,
+}
+
+impl WrappedSupply {
+    pub fn new() -> Self {
+        Self {
+            supply: LookupMap::new(b"wrapped_supply".to_vec()),
+            caps: LookupMap::new(b"wrapped_caps".to_vec()),
+        }
+    }
+
+    pub fn get_supply(&self, token: &AccountId) -> Balance {
+        self.supply.get(token).unwrap_or(0)
+    }
+
+    pub fn get_cap(&self, token: &AccountId) -> Option<Balance> {
+        self.caps.get(token)
+    }
+
+    pub fn set_cap(&mut self, token: &AccountId, cap: Option<Balance>) {
+        match cap {
+            Some(cap) => self.caps.insert(token, &cap),
+            None => self.caps.remove(token),
+        };
+    }
+
+    pub fn mint(&mut self, token: &AccountId, amount: Balance) {
+        let supply = self.get_supply(token) + amount;
+        if let Some(cap) = self.get_cap(token) {
+            require!(supply <= cap, "Supply: mint cap exceeded");
+        }
+
+        self.supply.insert(token, &supply);
+    }
+
+    pub fn burn(&mut self, token: &AccountId, amount: Balance) {
+        self.supply.insert(token, &self.get_supply(token).saturating_sub(amount));
+    }
+
+    /// Gives back the amount of a failed burn, the tokens still exist so the cap is not checked.
+    pub fn restore(&mut self, token: &AccountId, amount: Balance) {
+        self.supply.insert(token, &(self.get_supply(token) + amount));
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    /// Sets the maximal supply the bridge can mint of the wrapped token, `None` removes the cap.
+    pub fn set_mint_cap(&mut self, token: AccountId, cap: Option<U128>, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::SetMintCap).account(&token).borsh(&cap.map(|cap| cap.0))
+        );
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        let info = self.tokens.get_token(&token).unwrap_or_else(|| env::panic_str("Tokens: token is not registered"));
+        require!(info.is_wrapped(), "Supply: token is not wrapped");
+
+        self.wrapped_supply.set_cap(&token, cap.map(|cap| cap.0));
+    }
+
+    pub fn get_wrapped_supply(&self, token: AccountId) -> TokenSupply {
+        let info = self.tokens.get_token(&token).unwrap_or_else(|| env::panic_str("Tokens: token is not registered"));
+        require!(info.is_wrapped(), "Supply: token is not wrapped");
+
+        self.internal_token_supply(info)
+    }
+
+    /// Returns the supply of every wrapped token, of the tokens originating from `chain` if it is set.
+    pub fn get_wrapped_supplies(&self, chain: Option<String>) -> Vec<TokenSupply> {
+        self.tokens.get_tokens().into_iter()
+            .filter(|info| info.is_wrapped())
+            .filter(|info| chain.as_ref().map_or(true, |chain| &info.chain == chain))
+            .map(|info| self.internal_token_supply(info))
+            .collect()
+    }
+
+    /// Sends the deposited tokens to the burn address if the token has no `ft_burn`, the wrapped
+    /// tokens deployed before it still burn this way.
+    #[private]
+    pub fn ft_burn_callback(&mut self, token: AccountId, amount: U128) {
+        if is_promise_succeeded(0) {
+            return;
+        }
+
+        log!("Supply: burn of {} {} failed, the tokens are sent to the burn address", amount.0, token);
+        ext_fungible_token::ext(token.clone())
+            .with_static_gas(GAS_FOR_TX)
+            .with_attached_deposit(TRANSFER_DEPOSIT)
+            .ft_transfer(AccountId::new_unchecked(BURN_ADDRESS.to_string()), amount, None)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TX)
+                    .burn_callback(token, amount)
+            );
+    }
+
+    /// Sends the deposited token to the burn address if the token has no `nft_burn`.
+    #[private]
+    pub fn nft_burn_callback(&mut self, token: AccountId, token_id: TokenId) {
+        if is_promise_succeeded(0) {
+            return;
+        }
+
+        log!("Supply: burn of {} {} failed, the token is sent to the burn address", token, token_id);
+        self.internal_nft_transfer(token.clone(), token_id, AccountId::new_unchecked(BURN_ADDRESS.to_string()), TRANSFER_DEPOSIT)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_TX)
+                    .burn_callback(token, U128(1))
+            );
+    }
+
+    /// Keeps the supply in line with the token when neither the burn nor the transfer to the burn
+    /// address succeeded, the tokens stay on the bridge account.
+    #[private]
+    pub fn burn_callback(&mut self, token: AccountId, amount: U128) {
+        if is_promise_succeeded(0) {
+            return;
+        }
+
+        log!("Supply: burn of {} {} failed, the tokens stay on the bridge", amount.0, token);
+        self.wrapped_supply.restore(&token, amount.0);
+    }
+}
+
+impl Bridge {
+    fn internal_token_supply(&self, info: TokenInfo) -> TokenSupply {
+        TokenSupply {
+            supply: U128(self.wrapped_supply.get_supply(&info.token)),
+            mint_cap: self.wrapped_supply.get_cap(&info.token).map(U128),
+            seeded: self.locked.is_seeded(&Some(info.token.clone())),
+            token: info.token,
+            token_type: info.token_type,
+            chain: info.chain,
+        }
+    }
+
+    /// Adds the wrapped assets of the withdrawal to the supply before the mint, panics if the mint
+    /// cap is exceeded. Locked assets are released instead.
+    pub(crate) fn internal_mint_withdrawal(&mut self, withdrawal: &Withdrawal) {
+        match withdrawal.kind.clone() {
+            WithdrawalKind::FT { token, amount, .. } if self.tokens.is_wrapped(&token) => {
+                self.wrapped_supply.mint(&token, amount.0);
+            }
+            WithdrawalKind::NFT { token, .. } if self.tokens.is_wrapped(&token) => {
+                self.wrapped_supply.mint(&token, 1);
+            }
+            _ => {}
+        }
+    }
+
+    /// Takes the wrapped assets of a failed mint back from the supply.
+    pub(crate) fn internal_unmint_withdrawal(&mut self, withdrawal: &Withdrawal) {
+        match withdrawal.kind.clone() {
+            WithdrawalKind::FT { token, amount, .. } if self.tokens.is_wrapped(&token) => {
+                self.wrapped_supply.burn(&token, amount.0);
+            }
+            WithdrawalKind::NFT { token, .. } if self.tokens.is_wrapped(&token) => {
+                self.wrapped_supply.burn(&token, 1);
+            }
+            _ => {}
+        }
+    }
+}
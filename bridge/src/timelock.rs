@@ -0,0 +1,238 @@
+use near_sdk::{AccountId, Balance, env, near_bindgen, Promise};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::json_types::{U128, U64};
+use serde::{Deserialize, Serialize};
+
+use shared::{AuthorizedOperation, Hash, OperationType, RecoverableSignature};
+
+use crate::events_queue::WithdrawalQueueData;
+use crate::guardians::GuardianActionKind;
+
+use super::*;
+
+pub(crate) const NANOS_IN_SECOND: u64 = 1_000_000_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub struct Timelock {
+    // Withdrawals of at least this amount are queued
+    pub threshold: U128,
+    // Seconds a queued withdrawal waits before it can be claimed
+    pub delay: U64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub enum QueueStatus {
+    Queued,
+    Claimed,
+    Cancelled,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub struct QueuedWithdrawal {
+    pub withdrawal: Withdrawal,
+    pub root: String,
+    // Account that submitted the withdrawal, the deposit is returned to it on cancellation
+    pub sender: AccountId,
+    // Deposit attached to the original withdrawal, used to execute it on claim
+    pub deposit: U128,
+    pub release_at: U64,
+    pub status: QueueStatus,
+}
+
+/// Delay queue of the withdrawals which are not executed right away: the ones above the
+/// timelock threshold and the ones over the limits with the delay policy.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct WithdrawalQueue {
+    pub timelocks: LookupMap<Option<AccountId>, Timelock>,
+    pub queue: UnorderedMap<String, QueuedWithdrawal>,
+}
+
+impl WithdrawalQueue {
+    pub fn new() -> Self {
+        Self {
+            timelocks: LookupMap::new(b"timelocks".to_vec()),
+            queue: UnorderedMap::new(b"withdrawal_queue".to_vec()),
+        }
+    }
+
+    pub fn set_timelock(&mut self, token: &Option<AccountId>, timelock: Option<Timelock>) {
+        match timelock {
+            Some(timelock) => self.timelocks.insert(token, &timelock),
+            None => self.timelocks.remove(token),
+        };
+    }
+
+    pub fn get_timelock(&self, token: &Option<AccountId>) -> Option<Timelock> {
+        self.timelocks.get(token)
+    }
+
+    /// Returns the timelock delay if the amount reaches the token threshold.
+    pub fn get_delay(&self, token: &Option<AccountId>, amount: Balance) -> Option<u64> {
+        self.timelocks.get(token)
+            .filter(|timelock| amount >= timelock.threshold.0)
+            .map(|timelock| timelock.delay.0)
+    }
+
+    /// Queues the withdrawal, a claimed or cancelled entry of the same origin is replaced.
+    pub fn enqueue(&mut self, withdrawal: Withdrawal, root: Hash, sender: AccountId, deposit: Balance, delay: u64) -> QueuedWithdrawal {
+        assert!(
+            self.queue.get(&withdrawal.origin).map_or(true, |queued| queued.status != QueueStatus::Queued),
+            "Queue: withdrawal already queued",
+        );
+
+        let queued = QueuedWithdrawal {
+            withdrawal,
+            root: root.as_str(),
+            sender,
+            deposit: U128(deposit),
+            release_at: U64(env::block_timestamp() + delay * NANOS_IN_SECOND),
+            status: QueueStatus::Queued,
+        };
+        self.queue.insert(&queued.withdrawal.origin.clone(), &queued);
+
+        queued
+    }
+
+    /// Changes the status of a queued withdrawal and returns it.
+    pub fn resolve(&mut self, origin: &String, status: QueueStatus) -> QueuedWithdrawal {
+        let mut queued = self.queue.get(origin).unwrap_or_else(|| env::panic_str("Queue: withdrawal not found"));
+        assert_eq!(queued.status, QueueStatus::Queued, "Queue: withdrawal is already resolved");
+        if status == QueueStatus::Claimed {
+            assert!(queued.release_at.0 <= env::block_timestamp(), "Queue: withdrawal is not released yet");
+        }
+
+        queued.status = status;
+        self.queue.insert(origin, &queued);
+
+        queued
+    }
+
+    pub fn get(&self, origin: &String) -> Option<QueuedWithdrawal> {
+        self.queue.get(origin)
+    }
+
+    pub fn list(&self, status: Option<QueueStatus>, from_index: Option<u64>, limit: Option<u64>) -> Vec<QueuedWithdrawal> {
+        self.queue.values()
+            .filter(|queued| status.as_ref().map_or(true, |status| queued.status == *status))
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u64::MAX) as usize)
+            .collect()
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    /// Sets the timelock of the token, `None` token stands for native NEAR and `None` timelock
+    /// removes it.
+    pub fn set_token_timelock(
+        &mut self,
+        token: Option<AccountId>,
+        timelock: Option<Timelock>,
+        signatures: Vec<RecoverableSignature>,
+    ) {
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::SetTokenTimelock).borsh(&token).borsh(&timelock)
+        );
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.queue.set_timelock(&token, timelock);
+    }
+
+    pub fn get_token_timelock(&self, token: Option<AccountId>) -> Option<Timelock> {
+        self.queue.get_timelock(&token)
+    }
+
+    pub fn get_queued_withdrawal(&self, origin: String) -> Option<QueuedWithdrawal> {
+        self.queue.get(&origin)
+    }
+
+    pub fn get_queued_withdrawals(
+        &self,
+        status: Option<QueueStatus>,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<QueuedWithdrawal> {
+        self.queue.list(status, from_index, limit)
+    }
+
+    /// Executes the queued withdrawal once its delay has passed, can be called by anyone.
+    pub fn claim_queued_withdrawal(&mut self, origin: String) -> Promise {
+        Bridge::require_unpaused();
+
+        let queued = self.queue.resolve(&origin, QueueStatus::Claimed);
+        emit_queue_event(&queued, Some(&env::predecessor_account_id()));
+
+        self.internal_withdraw(queued.withdrawal, Hash::from_hex(queued.root), queued.deposit.0, queued.sender)
+    }
+
+    /// Cancels the queued withdrawal and releases its origin hash, so it can be signed again.
+    /// Only guardians are allowed to cancel. Queued withdrawals do not use the limits until they
+    /// are claimed, so there is no usage to release.
+    pub fn cancel_queued_withdrawal(&mut self, origin: String) {
+        let guardian = env::predecessor_account_id();
+        self.guardians.record_action(&guardian, GuardianActionKind::CancelWithdrawal { origin: origin.clone() });
+
+        let queued = self.queue.resolve(&origin, QueueStatus::Cancelled);
+        self.hashes.change_hash(queued.withdrawal.origin_hash(), false);
+        emit_queue_event(&queued, Some(&guardian));
+
+        if queued.deposit.0 > 0 {
+            Promise::new(queued.sender.clone()).transfer(queued.deposit.0);
+        }
+    }
+}
+
+impl Bridge {
+    /// Puts the withdrawal into the queue if it is over the limits with the delay policy or
+    /// reaches the timelock threshold. Returns `false` if the withdrawal was queued instead of
+    /// being executed.
+    pub(crate) fn internal_check_withdrawal_delay(&mut self, withdrawal: &Withdrawal, root: Hash, deposit: Balance) -> bool {
+        let (token, amount) = match withdrawal.limited_amount() {
+            Some(limited) => limited,
+            None => return true,
+        };
+
+        let delay = if self.limits.check(&token, amount) {
+            self.queue.get_delay(&token, amount)
+        } else {
+            Some(self.limits.get_limits(&token).map_or(0, |limits| limits.delay.0))
+        };
+
+        match delay {
+            Some(delay) => {
+                let queued = self.queue.enqueue(withdrawal.clone(), root, env::predecessor_account_id(), deposit, delay);
+                emit_queue_event(&queued, None);
+                false
+            }
+            None => true,
+        }
+    }
+}
+
+fn emit_queue_event(queued: &QueuedWithdrawal, sender: Option<&AccountId>) {
+    let withdrawal = &queued.withdrawal;
+    let release_at = queued.release_at.0.to_string();
+
+    let (token, token_id, amount) = match withdrawal.kind.clone() {
+        WithdrawalKind::Native { amount } => (None, None, Some(amount.0.to_string())),
+        WithdrawalKind::FT { token, amount, .. } => (Some(token.to_string()), None, Some(amount.0.to_string())),
+        WithdrawalKind::NFT { token, token_id, .. } => (Some(token.to_string()), Some(token_id), None),
+    };
+
+    let data = vec![WithdrawalQueueData::new(
+        &withdrawal.origin,
+        &withdrawal.receiver_id,
+        token.as_deref(),
+        token_id.as_deref(),
+        amount.as_deref(),
+        &release_at,
+        sender,
+    )];
+
+    match queued.status {
+        QueueStatus::Queued => NearEvent::withdrawal_queued(data).emit(),
+        QueueStatus::Claimed => NearEvent::withdrawal_claimed(data).emit(),
+        QueueStatus::Cancelled => NearEvent::withdrawal_cancelled(data).emit(),
+    }
+}
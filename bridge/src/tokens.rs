@@ -0,0 +1,99 @@
+use near_sdk::{AccountId, env, near_bindgen};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use serde::{Deserialize, Serialize};
+
+use shared::{AuthorizedOperation, OperationType, RecoverableSignature, TokenType};
+
+use super::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub enum TokenMode {
+    /// The token contract is owned by the bridge: withdrawals mint and deposits burn.
+    Wrapped,
+    /// The token is native to NEAR: withdrawals release and deposits lock it on the bridge.
+    Locked,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct TokenInfo {
+    pub token: AccountId,
+    pub token_type: TokenType,
+    pub mode: TokenMode,
+    // Chain the token originates from
+    pub chain: String,
+    pub decimals: u8,
+}
+
+impl TokenInfo {
+    pub fn is_wrapped(&self) -> bool {
+        self.mode == TokenMode::Wrapped
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct TokensManager {
+    pub tokens: UnorderedMap<AccountId, TokenInfo>,
+}
+
+impl TokensManager {
+    pub fn new() -> Self {
+        Self {
+            tokens: UnorderedMap::new(b"tokens".to_vec()),
+        }
+    }
+
+    pub fn add_token(&mut self, info: TokenInfo) {
+        assert!(info.token_type != TokenType::Native, "Tokens: native token can not be registered");
+        assert!(self.tokens.get(&info.token).is_none(), "Tokens: token already registered");
+        self.tokens.insert(&info.token.clone(), &info);
+    }
+
+    pub fn remove_token(&mut self, token: &AccountId) {
+        assert!(self.tokens.remove(token).is_some(), "Tokens: token is not registered");
+    }
+
+    pub fn get_token(&self, token: &AccountId) -> Option<TokenInfo> {
+        self.tokens.get(token)
+    }
+
+    /// Returns the registered token of the expected type, panics otherwise.
+    pub fn get_registered_token(&self, token: &AccountId, token_type: TokenType) -> TokenInfo {
+        let info = self.get_token(token).unwrap_or_else(|| env::panic_str("Tokens: token is not registered"));
+        assert_eq!(info.token_type, token_type, "Tokens: unexpected token type");
+        info
+    }
+
+    pub fn is_wrapped(&self, token: &AccountId) -> bool {
+        self.get_token(token).map_or(false, |info| info.is_wrapped())
+    }
+
+    pub fn get_tokens(&self) -> Vec<TokenInfo> {
+        self.tokens.values().collect()
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    pub fn get_token(&self, token: AccountId) -> Option<TokenInfo> {
+        self.tokens.get_token(&token)
+    }
+
+    pub fn get_tokens(&self) -> Vec<TokenInfo> {
+        self.tokens.get_tokens()
+    }
+
+    pub fn add_token(&mut self, info: TokenInfo, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::AddToken).borsh(&info));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.tokens.add_token(info);
+    }
+
+    pub fn remove_token(&mut self, token: AccountId, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::RemoveToken).account(&token));
+
+        self.check_signature_and_increment_nonce(hash, signatures);
+        self.tokens.remove_token(&token);
+    }
+}
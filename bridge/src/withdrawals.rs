@@ -0,0 +1,538 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, Balance, env, Gas, near_bindgen, Promise, PromiseResult, require};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use serde::{Deserialize, Serialize};
+
+use shared::{AuthorizedOperation, Bundle, ContentNode, Data, GAS_FOR_TRANSFER_CALL, GAS_FOR_TX, get_merkle_root, Hash, NFT_MINT_STORAGE_DEPOSIT, OperationType, RecoverableSignature, TokenType, TRANSFER_DEPOSIT};
+
+use super::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub enum WithdrawalKind {
+    Native {
+        amount: U128,
+    },
+    FT {
+        token: AccountId,
+        amount: U128,
+        // Delivers with `ft_transfer_call` if present
+        msg: Option<String>,
+    },
+    NFT {
+        token: AccountId,
+        token_id: TokenId,
+        token_metadata: NftMetadata,
+        // Delivers with `nft_transfer_call` if present
+        msg: Option<String>,
+    },
+}
+
+/// Leaf data of a single withdrawal, everything that is signed in the `ContentNode` except the
+/// bridge account and the chain.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone)]
+pub struct Withdrawal {
+    pub origin: String,
+    pub receiver_id: AccountId,
+    pub kind: WithdrawalKind,
+    // Calls executed by the receiver proxy after the assets are delivered to it
+    pub bundle: Option<Bundle>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WithdrawalItem {
+    pub withdrawal: Withdrawal,
+    pub path: Vec<Hash>,
+}
+
+impl Withdrawal {
+    pub fn new(origin: String, receiver_id: AccountId, kind: WithdrawalKind, bundle: Option<Bundle>) -> Self {
+        Self {
+            origin,
+            receiver_id,
+            kind,
+            bundle,
+        }
+    }
+
+    /// Account the assets are delivered to: the bundle proxy if there is a bundle, the receiver
+    /// otherwise.
+    pub fn delivery_account(&self) -> AccountId {
+        match self.bundle.clone() {
+            Some(bundle) => bundle.get_proxy_id(&env::current_account_id(), &self.receiver_id),
+            None => self.receiver_id.clone(),
+        }
+    }
+
+    /// Gas needed to run the bundle after the delivery.
+    pub fn bundle_gas(&self) -> Gas {
+        self.bundle.as_ref().map_or(Gas(0), |bundle| bundle.required_gas() + GAS_FOR_TX)
+    }
+
+    /// Gas needed to deliver with a transfer call and to forward the unused assets to the fallback
+    /// receiver.
+    pub fn transfer_call_gas(&self) -> Gas {
+        self.msg().map_or(Gas(0), |_| GAS_FOR_TRANSFER_CALL + GAS_FOR_TX + GAS_FOR_TX)
+    }
+
+    /// Gas of the callback which resolves the delivery.
+    pub fn callback_gas(&self) -> Gas {
+        GAS_FOR_TX + self.bundle_gas() + self.transfer_call_gas()
+    }
+
+    pub fn msg(&self) -> Option<String> {
+        match self.kind.clone() {
+            WithdrawalKind::Native { .. } => None,
+            WithdrawalKind::FT { msg, .. } => msg,
+            WithdrawalKind::NFT { msg, .. } => msg,
+        }
+    }
+
+    pub fn origin_hash(&self) -> Hash {
+        Hash::from_hex(self.origin.clone())
+    }
+
+    pub fn token_type(&self) -> TokenType {
+        match self.kind {
+            WithdrawalKind::Native { .. } => TokenType::Native,
+            WithdrawalKind::FT { .. } => TokenType::FT,
+            WithdrawalKind::NFT { .. } => TokenType::NFT,
+        }
+    }
+
+    pub fn token(&self) -> Option<AccountId> {
+        match self.kind.clone() {
+            WithdrawalKind::Native { .. } => None,
+            WithdrawalKind::FT { token, .. } => Some(token),
+            WithdrawalKind::NFT { token, .. } => Some(token),
+        }
+    }
+
+    /// Token and amount counted by the withdrawal limits, `None` for NFTs which are not limited.
+    pub fn limited_amount(&self) -> Option<(Option<AccountId>, Balance)> {
+        match self.kind.clone() {
+            WithdrawalKind::Native { amount } => Some((None, amount.0)),
+            WithdrawalKind::FT { token, amount, .. } => Some((Some(token), amount.0)),
+            WithdrawalKind::NFT { .. } => None,
+        }
+    }
+
+    pub fn content_node(&self, chain: String) -> ContentNode {
+        let mut data = match self.kind.clone() {
+            WithdrawalKind::Native { amount } => {
+                TransferOperation::new_native_transfer(amount.0).get_data()
+            }
+            WithdrawalKind::FT { token, amount, msg } => {
+                TransferFullMetaOperation::new_ft_transfer(token, amount, msg).get_data()
+            }
+            WithdrawalKind::NFT { token, token_id, token_metadata, msg } => {
+                TransferFullMetaOperation::new_nft_transfer(
+                    token_id,
+                    Some(token),
+                    token_metadata.title.unwrap(),
+                    token_metadata.media.unwrap(),
+                    base64::encode(token_metadata.media_hash.unwrap().0),
+                    msg,
+                    token_metadata.royalty,
+                ).get_data()
+            }
+        };
+
+        if let Some(bundle) = self.bundle.clone() {
+            data.append(&mut bundle.get_data());
+        }
+
+        ContentNode::new(
+            self.origin_hash(),
+            env::current_account_id(),
+            chain,
+            data,
+            Some(self.receiver_id.clone()),
+        )
+    }
+}
+
+#[near_bindgen]
+impl Bridge {
+    /// Executes several withdrawals signed under the same Merkle root. The root is verified and
+    /// stored on the first call, so `signatures` may be omitted for an already submitted root.
+    /// Withdrawals that would be queued by the limits or the timelock fail the whole batch.
+    /// The deposit above the `quote_withdraw_deposit` sum pays the storage registration of the FT
+    /// receivers, the unused part is refunded.
+    #[payable]
+    pub fn batch_withdraw(
+        &mut self,
+        root: String,
+        signatures: Option<Vec<RecoverableSignature>>,
+        items: Vec<WithdrawalItem>,
+    ) -> Promise {
+        Bridge::require_unpaused();
+
+        require!(!items.is_empty(), "Batch must contain at least one withdrawal");
+
+        let root_hash = Hash::from_hex(root);
+        if self.roots.get_root(root_hash.clone()).is_none() {
+            let signatures = signatures.expect("Signatures are required for the root that is not submitted");
+            self.check_signature(root_hash.clone(), signatures);
+            self.roots.add_root(root_hash.clone());
+        }
+
+        let required_deposit: Balance = items.iter().map(|item| self.internal_withdrawal_deposit(&item.withdrawal)).sum();
+        require!(
+            env::attached_deposit() >= required_deposit,
+            format!("Requires attached deposit of at least {} yoctoNEAR", required_deposit),
+        );
+
+        // The surplus is shared between the FT withdrawals to pay the storage registration of their
+        // receivers, every delivery refunds the part it does not use
+        let surplus = env::attached_deposit() - required_deposit;
+        let ft_items = items.iter().filter(|item| item.withdrawal.token_type() == TokenType::FT).count() as Balance;
+        let registration_allowance = if ft_items > 0 { surplus / ft_items } else { 0 };
+        let unshared = surplus - registration_allowance * ft_items;
+        if unshared > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(unshared);
+        }
+
+        let mut withdrawals: Vec<Withdrawal> = Vec::new();
+        let mut promise: Option<Promise> = None;
+
+        for item in items.into_iter() {
+            let withdrawal = item.withdrawal;
+            let content = withdrawal.content_node(self.chain.clone());
+
+            require!(get_merkle_root(content, &item.path) == root_hash, "Withdrawal does not belong to the root");
+            self.hashes.check_hash(withdrawal.origin_hash());
+
+            let mut deposit = self.internal_withdrawal_deposit(&withdrawal);
+            if withdrawal.token_type() == TokenType::FT {
+                deposit += registration_allowance;
+            }
+            require!(
+                self.internal_check_withdrawal_delay(&withdrawal, root_hash.clone(), deposit),
+                "Queue: withdrawal can not be queued in a batch",
+            );
+
+            let next = self.internal_start_withdrawal(&withdrawal, deposit, &env::predecessor_account_id());
+            promise = Some(match promise {
+                Some(promise) => promise.and(next),
+                None => next,
+            });
+
+            withdrawals.push(withdrawal);
+        }
+
+        let callback_gas = withdrawals.iter().fold(GAS_FOR_TX, |gas, withdrawal| {
+            gas + withdrawal.bundle_gas() + withdrawal.transfer_call_gas()
+        });
+
+        promise.unwrap().then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(callback_gas)
+                .batch_withdraw_callback(withdrawals, env::predecessor_account_id(), root_hash)
+        )
+    }
+
+    /// Returns the execution result of every withdrawal in the batch, in the same order.
+    #[private]
+    pub fn batch_withdraw_callback(&mut self, withdrawals: Vec<Withdrawal>, sender: AccountId, root: Hash) -> Vec<bool> {
+        withdrawals.iter().enumerate().map(|(idx, withdrawal)| {
+            let result = get_promise_result(idx as u64);
+            let is_succeeded = result.is_some();
+            self.internal_resolve_withdrawal(result, withdrawal, &sender, root.clone());
+            is_succeeded
+        }).collect()
+    }
+
+    /// Resolves the transfer call of a wrapped token which was minted to the bridge, the unused
+    /// part is forwarded to the fallback receiver.
+    #[private]
+    pub fn transfer_call_callback(&mut self, withdrawal: Withdrawal) {
+        self.internal_forward_unused(&withdrawal, get_promise_result(0));
+    }
+
+    /// Returns the expected deposit of a withdrawal of the token, the token is `None` for native.
+    /// Fungible token withdrawals require the storage registration of an unregistered receiver on
+    /// top of it, see the `storage_balance_bounds` of the token.
+    pub fn quote_withdraw_deposit(&self, token: Option<AccountId>, kind: TokenType) -> U128 {
+        U128(self.internal_quote_withdraw_deposit(token, kind))
+    }
+
+    pub fn get_fallback_receiver(&self) -> Option<AccountId> {
+        self.fallback_receiver.clone()
+    }
+
+    /// Sets the account that receives the assets which were not used by the receiver of a
+    /// withdrawal with a message.
+    pub fn set_fallback_receiver(&mut self, receiver: Option<AccountId>, signatures: Vec<RecoverableSignature>) {
+        let hash = self.get_authorized_operation_hash(AuthorizedOperation::new(OperationType::SetFallbackReceiver).borsh(&receiver));
+        self.check_signature_and_increment_nonce(hash, signatures);
+
+        self.fallback_receiver = receiver;
+    }
+}
+
+impl Bridge {
+    /// The deposit which has to be attached to execute the withdrawal, panics if the withdrawn
+    /// token is not registered.
+    pub(crate) fn internal_withdrawal_deposit(&self, withdrawal: &Withdrawal) -> Balance {
+        self.internal_quote_withdraw_deposit(withdrawal.token(), withdrawal.token_type())
+    }
+
+    /// Minimal deposit of a withdrawal of the token. Mints of wrapped tokens measure the storage they
+    /// use and refund the surplus to the sender, so it is an upper estimate for them.
+    pub(crate) fn internal_quote_withdraw_deposit(&self, token: Option<AccountId>, kind: TokenType) -> Balance {
+        if kind == TokenType::Native {
+            return TRANSFER_DEPOSIT;
+        }
+
+        let token = token.unwrap_or_else(|| env::panic_str("Tokens: token is required"));
+        let mode = self.tokens.get_registered_token(&token, kind.clone()).mode;
+
+        match (kind, mode) {
+            // The storage registration of the receiver is paid on top of it if needed
+            (TokenType::FT, TokenMode::Wrapped) => 0,
+            (TokenType::NFT, TokenMode::Wrapped) => NFT_MINT_STORAGE_DEPOSIT,
+            _ => TRANSFER_DEPOSIT,
+        }
+    }
+
+    /// Checks that the withdrawal is included into a submitted root and marks its origin as used.
+    pub(crate) fn internal_verify_withdrawal(&mut self, withdrawal: &Withdrawal, path: &Vec<Hash>) -> Hash {
+        let root = get_merkle_root(withdrawal.content_node(self.chain.clone()), path);
+
+        self.roots.check_root(root.clone());
+        self.hashes.check_hash(withdrawal.origin_hash());
+
+        root
+    }
+
+    pub(crate) fn internal_withdraw(&mut self, withdrawal: Withdrawal, root: Hash, deposit: Balance, sender: AccountId) -> Promise {
+        self.internal_start_withdrawal(&withdrawal, deposit, &sender)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(withdrawal.callback_gas())
+                    .handle_hash_callback(withdrawal, sender, root)
+            )
+    }
+
+    /// Starts the delivery of the withdrawal and takes its assets and limit usage into account,
+    /// `internal_resolve_withdrawal` gives them back if the delivery fails.
+    fn internal_start_withdrawal(&mut self, withdrawal: &Withdrawal, deposit: Balance, sender: &AccountId) -> Promise {
+        let promise = self.internal_withdrawal_promise(withdrawal, deposit, sender);
+        self.internal_unlock_withdrawal(withdrawal);
+        self.internal_mint_withdrawal(withdrawal);
+        self.internal_record_limit_usage(withdrawal);
+
+        promise
+    }
+
+    /// Creates the mint or transfer promise that delivers the withdrawn assets to the receiver.
+    /// Every withdrawal path ends here, so the scoped pause is checked here as well. The `sender`
+    /// receives the part of the deposit which is not used for the delivery.
+    pub(crate) fn internal_withdrawal_promise(&self, withdrawal: &Withdrawal, deposit: Balance, sender: &AccountId) -> Promise {
+        self.pause_scopes.require_withdrawal_unpaused(withdrawal.token());
+
+        let receiver_id = withdrawal.delivery_account();
+        if withdrawal.bundle.is_some() {
+            require!(self.bundle_proxies.is_deployed(&receiver_id), "Bundles: proxy is not deployed");
+        }
+        if withdrawal.msg().is_some() {
+            require!(withdrawal.bundle.is_none(), "Withdrawals: message can not be used with a bundle");
+            require!(self.fallback_receiver.is_some(), "Withdrawals: fallback receiver is not set");
+        }
+
+        match withdrawal.kind.clone() {
+            WithdrawalKind::Native { amount } => {
+                Promise::new(receiver_id).transfer(amount.0)
+            }
+            WithdrawalKind::FT { token, msg, .. } => {
+                let is_wrapped = self.tokens.get_registered_token(&token, TokenType::FT).is_wrapped();
+
+                // The transfer call of wrapped tokens is made from the bridge balance after the mint
+                let account_id = match (is_wrapped, msg) {
+                    (true, Some(_)) => env::current_account_id(),
+                    _ => receiver_id,
+                };
+
+                self.internal_ft_delivery_promise(withdrawal, token, account_id, deposit, sender)
+            }
+            WithdrawalKind::NFT { token, token_id, token_metadata, msg } => {
+                let is_wrapped = self.tokens.get_registered_token(&token, TokenType::NFT).is_wrapped();
+
+                match (is_wrapped, msg) {
+                    (true, Some(_)) => {
+                        ext_non_fungible_token::ext(token)
+                            .with_static_gas(GAS_FOR_TX)
+                            .with_attached_deposit(deposit)
+                            .nft_mint(token_id, env::current_account_id(), token_metadata.to_token_metadata(), Some(sender.clone()), token_metadata.royalty)
+                    }
+                    (true, None) => {
+                        ext_non_fungible_token::ext(token)
+                            .with_static_gas(GAS_FOR_TX)
+                            .with_attached_deposit(deposit)
+                            .nft_mint(token_id, receiver_id, token_metadata.to_token_metadata(), Some(sender.clone()), token_metadata.royalty)
+                    }
+                    (false, Some(msg)) => {
+                        ext_non_fungible_token::ext(token)
+                            .with_static_gas(GAS_FOR_TRANSFER_CALL)
+                            .with_attached_deposit(deposit)
+                            .nft_transfer_call(receiver_id, token_id, None, None, msg)
+                    }
+                    (false, None) => self.internal_nft_transfer(token, token_id, receiver_id, deposit),
+                }
+            }
+        }
+    }
+
+    /// Makes the transfer call of the wrapped assets which were minted to the bridge.
+    fn internal_wrapped_transfer_call(&self, withdrawal: &Withdrawal) -> Option<Promise> {
+        let promise = match withdrawal.kind.clone() {
+            WithdrawalKind::FT { token, amount, msg: Some(msg) } if self.tokens.is_wrapped(&token) => {
+                ext_fungible_token::ext(token)
+                    .with_static_gas(GAS_FOR_TRANSFER_CALL)
+                    .with_attached_deposit(TRANSFER_DEPOSIT)
+                    .ft_transfer_call(withdrawal.receiver_id.clone(), amount, None, msg)
+            }
+            WithdrawalKind::NFT { token, token_id, msg: Some(msg), .. } if self.tokens.is_wrapped(&token) => {
+                ext_non_fungible_token::ext(token)
+                    .with_static_gas(GAS_FOR_TRANSFER_CALL)
+                    .with_attached_deposit(TRANSFER_DEPOSIT)
+                    .nft_transfer_call(withdrawal.receiver_id.clone(), token_id, None, None, msg)
+            }
+            _ => return None,
+        };
+
+        Some(promise.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_TX + GAS_FOR_TX)
+                .transfer_call_callback(withdrawal.clone())
+        ))
+    }
+
+    /// Forwards the assets returned by the receiver of a transfer call to the fallback receiver.
+    /// `result` is the value of the transfer call, `None` if it failed and nothing was delivered.
+    pub(crate) fn internal_forward_unused(&self, withdrawal: &Withdrawal, result: Option<Vec<u8>>) {
+        let fallback = self.fallback_receiver.clone().unwrap_or_else(|| withdrawal.receiver_id.clone());
+
+        match withdrawal.kind.clone() {
+            WithdrawalKind::FT { token, amount, .. } => {
+                // `ft_transfer_call` returns the amount used by the receiver
+                let used = result
+                    .and_then(|value| serde_json::from_slice::<U128>(&value).ok())
+                    .map_or(0, |used| used.0);
+                let unused = amount.0.saturating_sub(used);
+
+                if unused > 0 {
+                    ext_fungible_token::ext(token)
+                        .with_static_gas(GAS_FOR_TX)
+                        .with_attached_deposit(TRANSFER_DEPOSIT)
+                        .ft_transfer(fallback, U128(unused), None);
+                }
+            }
+            WithdrawalKind::NFT { token, token_id, .. } => {
+                // `nft_transfer_call` returns `true` if the token stays with the receiver
+                let is_kept = result
+                    .and_then(|value| serde_json::from_slice::<bool>(&value).ok())
+                    .unwrap_or(false);
+
+                if !is_kept {
+                    self.internal_nft_transfer(token, token_id, fallback, TRANSFER_DEPOSIT);
+                }
+            }
+            WithdrawalKind::Native { .. } => {}
+        }
+    }
+
+    /// Keeps a failed withdrawal in the pending store so it can be retried, the origin hash stays
+    /// used. Emits the withdrawn event on success and the withdrawal failed event otherwise.
+    /// The bundle of the withdrawal is executed only after a successful delivery, as well as the
+    /// transfer call of wrapped assets with a message. `result` is `None` if the delivery failed.
+    pub(crate) fn internal_resolve_withdrawal(&mut self, result: Option<Vec<u8>>, withdrawal: &Withdrawal, sender: &AccountId, root: Hash) {
+        let is_succeeded = result.is_some();
+        self.internal_settle_withdrawal(withdrawal);
+
+        if is_succeeded {
+            self.pending.remove(&withdrawal.origin);
+            self.internal_execute_bundle(withdrawal);
+
+            if withdrawal.msg().is_some() && self.internal_wrapped_transfer_call(withdrawal).is_none() {
+                // Locked assets were delivered with the transfer call itself
+                self.internal_forward_unused(withdrawal, result);
+            }
+        } else {
+            self.pending.mark_failed(withdrawal, root.clone());
+            self.internal_relock_withdrawal(withdrawal);
+            self.internal_unmint_withdrawal(withdrawal);
+            self.internal_release_limit_usage(withdrawal);
+        }
+
+        let root = root.as_str();
+        let receiver = withdrawal.receiver_id.clone();
+
+        match withdrawal.kind.clone() {
+            WithdrawalKind::NFT { token, token_id, .. } => {
+                let data = vec![NftWithdrawnData::new(
+                    &token,
+                    &token_id,
+                    sender,
+                    &receiver,
+                    &withdrawal.origin,
+                    &root,
+                    self.tokens.is_wrapped(&token),
+                )];
+
+                if is_succeeded {
+                    NearEvent::nft_withdrawn(data).emit();
+                } else {
+                    NearEvent::nft_withdrawal_failed(data).emit();
+                }
+            }
+            WithdrawalKind::FT { token, amount, .. } => {
+                let amount = amount.0.to_string();
+                let data = vec![FtWithdrawnData::new(
+                    &token,
+                    &amount,
+                    sender,
+                    &receiver,
+                    &withdrawal.origin,
+                    &root,
+                    self.tokens.is_wrapped(&token),
+                )];
+
+                if is_succeeded {
+                    NearEvent::ft_withdrawn(data).emit();
+                } else {
+                    NearEvent::ft_withdrawal_failed(data).emit();
+                }
+            }
+            WithdrawalKind::Native { amount } => {
+                let amount = amount.0.to_string();
+                let data = vec![NativeWithdrawnData::new(
+                    &amount,
+                    sender,
+                    &receiver,
+                    &withdrawal.origin,
+                    &root,
+                )];
+
+                if is_succeeded {
+                    NearEvent::native_withdrawn(data).emit();
+                } else {
+                    NearEvent::native_withdrawal_failed(data).emit();
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn is_promise_succeeded(idx: u64) -> bool {
+    matches!(env::promise_result(idx), PromiseResult::Successful(_))
+}
+
+/// Returns the value of the promise or `None` if it failed.
+pub(crate) fn get_promise_result(idx: u64) -> Option<Vec<u8>> {
+    match env::promise_result(idx) {
+        PromiseResult::Successful(value) => Some(value),
+        _ => None,
+    }
+}
@@ -0,0 +1,72 @@
+/*!
+Bundle proxy, deployed by the bridge as a sub-account per receiver and salt.
+NOTES:
+  - Withdrawals with a bundle deliver the assets to the proxy and then call `execute`.
+  - The calls run one after another, the execution stops at the first failed call.
+  - Assets that are left on the proxy after a failed or partial execution stay there, the
+    receiver (`owner_id`) can move them with its own `execute` call.
+ */
+use near_sdk::{AccountId, env, Gas, near_bindgen, PanicOnDefault, Promise, PromiseOrValue, PromiseResult};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+use shared::{BundleCall, GAS_FOR_BUNDLE_STEP};
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct BundleProxy {
+    pub bridge: AccountId,
+    pub owner_id: AccountId,
+}
+
+#[near_bindgen]
+impl BundleProxy {
+    #[init]
+    pub fn new(bridge: AccountId, owner_id: AccountId) -> Self {
+        Self {
+            bridge,
+            owner_id,
+        }
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    /// Executes the calls one after another and returns `true` if all of them succeeded.
+    /// Can be called by the bridge or the owner.
+    pub fn execute(&mut self, calls: Vec<BundleCall>) -> PromiseOrValue<bool> {
+        let caller = env::predecessor_account_id();
+        assert!(caller == self.bridge || caller == self.owner_id, "Only bridge or owner can execute calls");
+
+        internal_execute(calls)
+    }
+
+    #[private]
+    pub fn execute_next(&mut self, calls: Vec<BundleCall>) -> PromiseOrValue<bool> {
+        if !matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+            env::log_str(format!("Bundle call failed, {} calls skipped", calls.len()).as_str());
+            return PromiseOrValue::Value(false);
+        }
+
+        internal_execute(calls)
+    }
+}
+
+fn internal_execute(mut calls: Vec<BundleCall>) -> PromiseOrValue<bool> {
+    if calls.is_empty() {
+        return PromiseOrValue::Value(true);
+    }
+
+    let call = calls.remove(0);
+    let remaining_gas = calls.iter().fold(GAS_FOR_BUNDLE_STEP, |gas, call| gas + Gas(call.gas.0) + GAS_FOR_BUNDLE_STEP);
+
+    let promise = Promise::new(call.contract)
+        .function_call(call.method, call.args.0, call.deposit.0, Gas(call.gas.0))
+        .then(
+            BundleProxy::ext(env::current_account_id())
+                .with_static_gas(remaining_gas)
+                .execute_next(calls)
+        );
+
+    PromiseOrValue::from(promise)
+}
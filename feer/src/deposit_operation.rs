@@ -5,11 +5,10 @@ use near_sdk::env::panic_str;
 use near_sdk::json_types::U128;
 use serde::{Deserialize, Serialize};
 
-use shared::{ContentNode, Data, GAS_FOR_TX, get_merkle_root, Hash, Secp256K1Signature, SignerPublicKey, TransferLog, TokenType, verify_ecdsa_signature};
+use shared::{AuthorizedOperation, GAS_FOR_TX, OperationType, SignerSet, TransferLog, TokenType};
 
 use crate::fee_tokens::{FeeToken};
-use crate::merkle::OperationData;
-use crate::types::{DepositLog, FeeManageOperation, OperationType, TransferType};
+use crate::types::{DepositLog, FeeManageOperation, TransferType};
 
 use super::*;
 
@@ -112,7 +111,7 @@ impl Feer {
         &mut self,
         op: FeeManageOperation,
     ) -> Promise {
-        ext_bridge::ext(self.bridge_addr.clone()).get_signer()
+        ext_bridge::ext(self.bridge_addr.clone()).get_signers()
             .then(
                 Self::ext(env::current_account_id())
                     .with_static_gas(GAS_FOR_TX)
@@ -124,7 +123,7 @@ impl Feer {
         &mut self,
         op: FeeManageOperation,
     ) -> Promise {
-        ext_bridge::ext(self.bridge_addr.clone()).get_signer()
+        ext_bridge::ext(self.bridge_addr.clone()).get_signers()
             .then(
                 Self::ext(env::current_account_id())
                     .with_static_gas(GAS_FOR_TX)
@@ -133,7 +132,7 @@ impl Feer {
     }
 
     pub fn remove_fee_token(&mut self, op: FeeManageOperation) -> Promise {
-        ext_bridge::ext(self.bridge_addr.clone()).get_signer()
+        ext_bridge::ext(self.bridge_addr.clone()).get_signers()
             .then(
                 Self::ext(env::current_account_id())
                     .with_static_gas(GAS_FOR_TX)
@@ -142,7 +141,7 @@ impl Feer {
     }
 
     pub fn withdraw(&mut self, op: FeeManageOperation, amount: U128, receiver: AccountId) -> Promise {
-        ext_bridge::ext(self.bridge_addr.clone()).get_signer()
+        ext_bridge::ext(self.bridge_addr.clone()).get_signers()
             .then(
                 Self::ext(env::current_account_id())
                     .with_static_gas(GAS_FOR_TX)
@@ -160,70 +159,66 @@ impl Feer {
 
 
     #[private]
-    pub fn get_signer_public_key(&self) -> PromiseOrValue<SignerPublicKey> {
-        PromiseOrValue::Promise(ext_bridge::ext(self.bridge_addr.clone()).get_signer())
+    pub fn get_signer_set(&self) -> PromiseOrValue<SignerSet> {
+        PromiseOrValue::Promise(ext_bridge::ext(self.bridge_addr.clone()).get_signers())
     }
 
     #[private]
     pub fn add_fee_token_callback(
         &mut self,
-        #[callback_result] call_result: Result<SignerPublicKey, PromiseError>,
+        #[callback_result] call_result: Result<SignerSet, PromiseError>,
         op: FeeManageOperation,
     ) {
-        self.handle_operation(
-            unwrap_signer_public_key(call_result),
-            OperationType::AddFeeToken,
-            op.clone(),
-            None,
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::AddFeeToken).borsh(&op.token)
         );
+        self.check_signature_and_increment_nonce(unwrap_signer_set(call_result), hash, &op.signatures);
         self.tokens.add_fee_token(op.token);
     }
 
     #[private]
     pub fn update_fee_token_callback(
         &mut self,
-        #[callback_result] call_result: Result<SignerPublicKey, PromiseError>,
+        #[callback_result] call_result: Result<SignerSet, PromiseError>,
         op: FeeManageOperation,
     ) {
-        self.handle_operation(
-            unwrap_signer_public_key(call_result),
-            OperationType::UpdateFeeToken,
-            op.clone(),
-            None,
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::UpdateFeeToken).borsh(&op.token)
         );
+        self.check_signature_and_increment_nonce(unwrap_signer_set(call_result), hash, &op.signatures);
         self.tokens.update_fee_token(op.token);
     }
 
     #[private]
     pub fn remove_fee_token_callback(
         &mut self,
-        #[callback_result] call_result: Result<SignerPublicKey, PromiseError>,
+        #[callback_result] call_result: Result<SignerSet, PromiseError>,
         op: FeeManageOperation,
     ) {
-        self.handle_operation(
-            unwrap_signer_public_key(call_result),
-            OperationType::RemoveFeeToken,
-            op.clone(),
-            None,
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::RemoveFeeToken).borsh(&op.token.token_addr)
         );
+        self.check_signature_and_increment_nonce(unwrap_signer_set(call_result), hash, &op.signatures);
         self.tokens.remove_fee_token(op.token.token_addr);
     }
 
     #[private]
     pub fn withdraw_callback(
         &mut self,
-        #[callback_result] call_result: Result<SignerPublicKey, PromiseError>,
+        #[callback_result] call_result: Result<SignerSet, PromiseError>,
         op: FeeManageOperation,
         amount: U128,
         receiver: AccountId,
     ) {
-        self.handle_operation(
-            unwrap_signer_public_key(call_result),
-            OperationType::Withdraw,
-            op.clone(),
-            Some(amount.clone()),
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::WithdrawFees)
+                .borsh(&op.token.token_addr)
+                .borsh(&op.token.token_type)
+                .number(amount.0)
+                .account(&receiver)
         );
-        self.internal_withdraw(op.token.clone(), amount.clone(), receiver.clone());
+        self.check_signature_and_increment_nonce(unwrap_signer_set(call_result), hash, &op.signatures);
+        self.internal_withdraw(op.token, amount, receiver);
     }
 
     #[private]
@@ -233,6 +228,8 @@ impl Feer {
                 Promise::new(receiver).transfer(amount.into());
             }
             TokenType::FT => {
+                self.held.release_ft(fee_token.token_addr.as_ref().unwrap(), amount.0);
+
                 ext_fungible_token::ext(fee_token.token_addr.unwrap().clone())
                     .with_static_gas(GAS_FOR_TX)
                     .with_attached_deposit(ONE_YOCTO)
@@ -243,29 +240,6 @@ impl Feer {
             }
         }
     }
-
-    #[private]
-    pub fn handle_operation(&mut self, signer: SignerPublicKey, op_type: OperationType, op: FeeManageOperation, amount: Option<U128>) {
-        let signature = Secp256K1Signature::from_hex(op.clone().signature);
-        let origin = Hash::from_hex(op.origin.clone());
-        let receiver: Option<AccountId> = match op_type.clone() {
-            OperationType::Withdraw => Some(env::current_account_id().clone()),
-            _ => None,
-        };
-
-        let data = OperationData::new(op_type.clone(), op.clone().token, amount).get_data();
-
-        let content = ContentNode::new(
-            origin,
-            self.bridge_addr.clone(),
-            self.chain.clone(),
-            data,
-            receiver,
-        );
-
-        verify_ecdsa_signature(signer, get_merkle_root(content, &op.path), signature, op.recovery_id);
-        self.hashes.check_hash(origin.clone());
-    }
 }
 
 fn msg_from_deposit_log(sender: AccountId, log: DepositLog) -> String {
@@ -273,7 +247,6 @@ fn msg_from_deposit_log(sender: AccountId, log: DepositLog) -> String {
         sender,
         receiver: log.receiver.clone(),
         chain_to: log.chain_to.clone(),
-        is_wrapped: log.is_wrapped,
         bundle_data: log.bundle_data.clone(),
         bundle_salt: log.bundle_salt.clone(),
     };
@@ -281,9 +254,9 @@ fn msg_from_deposit_log(sender: AccountId, log: DepositLog) -> String {
     serde_json::to_string(&transfer_log).unwrap()
 }
 
-fn unwrap_signer_public_key(call_result: Result<SignerPublicKey, PromiseError>) -> SignerPublicKey {
+pub(crate) fn unwrap_signer_set(call_result: Result<SignerSet, PromiseError>) -> SignerSet {
     match call_result {
-        Ok(signer) => signer,
-        Err(_) => panic_str("failed to get bridge signer public key"),
+        Ok(signers) => signers,
+        Err(_) => panic_str("failed to get bridge signer set"),
     }
 }
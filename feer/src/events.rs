@@ -0,0 +1,19 @@
+use near_sdk::{AccountId, env};
+use near_sdk::serde_json::json;
+
+/// Logs the NEP-297 `tokens_rescued` event.
+pub fn emit_tokens_rescued(token: &AccountId, token_id: Option<&str>, amount: Option<&str>, receiver: &AccountId) {
+    let event = json!({
+        "standard": "feer",
+        "version": "1.0.0",
+        "event": "tokens_rescued",
+        "data": [{
+            "token": token,
+            "token_id": token_id,
+            "amount": amount,
+            "receiver": receiver,
+        }],
+    });
+
+    env::log_str(&format!("EVENT_JSON:{}", event));
+}
@@ -1,11 +1,11 @@
-use near_contract_standards::non_fungible_token::TokenId;
-use shared::{SignerPublicKey};
+use near_contract_standards::non_fungible_token::{Token, TokenId};
+use shared::{SignerSet};
 use near_sdk::{AccountId, ext_contract, PromiseOrValue};
 use near_sdk::json_types::U128;
 
 #[ext_contract(ext_bridge)]
 trait Bridge {
-    fn get_signer(&self) -> SignerPublicKey;
+    fn get_signers(&self) -> SignerSet;
     fn native_deposit(
         &mut self,
         sender: AccountId,
@@ -31,6 +31,7 @@ trait FtToken {
         amount: U128,
         memo: Option<String>,
     );
+    fn ft_balance_of(&self, account_id: AccountId) -> U128;
 }
 
 #[ext_contract(ext_non_fungible_token)]
@@ -43,4 +44,12 @@ trait NftToken {
         memo: Option<String>,
         msg: String,
     ) -> PromiseOrValue<bool>;
+    fn nft_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    );
+    fn nft_token(&self, token_id: TokenId) -> Option<Token>;
 }
@@ -0,0 +1,126 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, Balance, env, Gas, near_bindgen, Promise, PromiseError, require};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, LookupSet};
+use near_sdk::env::panic_str;
+use near_sdk::json_types::U128;
+
+use shared::{AuthorizedOperation, GAS_FOR_TX, OperationType, RecoverableSignature, SignerSet};
+
+use crate::deposit_operation::unwrap_signer_set;
+
+use super::*;
+
+/// Tokens the contract expects to hold: collected fees and deposits waiting for their fee.
+///
+/// A contract migrated from the state before the tracking starts with nothing held, every token is
+/// seeded once with the fees collected until then and can not be rescued before.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct HeldTokens {
+    pub ft: LookupMap<AccountId, Balance>,
+    pub nft: LookupSet<(AccountId, TokenId)>,
+    pub seeding_required: bool,
+    pub seeded: LookupSet<AccountId>,
+}
+
+impl HeldTokens {
+    pub fn new() -> Self {
+        Self {
+            ft: LookupMap::new(b"held_ft".to_vec()),
+            nft: LookupSet::new(b"held_nft".to_vec()),
+            seeding_required: false,
+            seeded: LookupSet::new(b"held_seeded".to_vec()),
+        }
+    }
+
+    /// Held tokens of a contract which collected fees before the tracking existed.
+    pub fn unseeded() -> Self {
+        Self {
+            seeding_required: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn is_seeded(&self, token: &AccountId) -> bool {
+        !self.seeding_required || self.seeded.contains(token)
+    }
+
+    pub fn get_ft(&self, token: &AccountId) -> Balance {
+        self.ft.get(token).unwrap_or(0)
+    }
+
+    pub fn hold_ft(&mut self, token: &AccountId, amount: Balance) {
+        self.ft.insert(token, &(self.get_ft(token) + amount));
+    }
+
+    pub fn release_ft(&mut self, token: &AccountId, amount: Balance) {
+        self.ft.insert(token, &self.get_ft(token).saturating_sub(amount));
+    }
+
+    pub fn is_nft_held(&self, token: &AccountId, token_id: &TokenId) -> bool {
+        self.nft.contains(&(token.clone(), token_id.clone()))
+    }
+
+    pub fn hold_nft(&mut self, token: &AccountId, token_id: &TokenId) {
+        self.nft.insert(&(token.clone(), token_id.clone()));
+    }
+
+    pub fn release_nft(&mut self, token: &AccountId, token_id: &TokenId) {
+        self.nft.remove(&(token.clone(), token_id.clone()));
+    }
+}
+
+#[near_bindgen]
+impl Feer {
+    /// Seeds the tokens a migrated contract held before the tracking existed: `amount` sets the held
+    /// balance of a fungible token, `token_ids` are the held NFTs. Both must include the fees collected
+    /// since the upgrade. Every token is seeded once, signed by the bridge signers.
+    pub fn seed_held_tokens(
+        &mut self,
+        token: AccountId,
+        amount: Option<U128>,
+        token_ids: Option<Vec<TokenId>>,
+        signatures: Vec<RecoverableSignature>,
+    ) -> Promise {
+        ext_bridge::ext(self.bridge_addr.clone()).get_signers()
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas(GAS_FOR_TX.0 * 2))
+                    .seed_held_tokens_callback(token, amount, token_ids, signatures)
+            )
+    }
+
+    #[private]
+    pub fn seed_held_tokens_callback(
+        &mut self,
+        #[callback_result] call_result: Result<SignerSet, PromiseError>,
+        token: AccountId,
+        amount: Option<U128>,
+        token_ids: Option<Vec<TokenId>>,
+        signatures: Vec<RecoverableSignature>,
+    ) {
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::SeedHeldTokens)
+                .account(&token)
+                .borsh(&amount.map(|amount| amount.0))
+                .borsh(&token_ids)
+        );
+        self.check_signature_and_increment_nonce(unwrap_signer_set(call_result), hash, &signatures);
+
+        require!(!self.held.is_seeded(&token), "Held: token is already seeded");
+
+        match (amount, token_ids) {
+            (Some(amount), None) => {
+                self.held.ft.insert(&token, &amount.0);
+            }
+            (None, Some(token_ids)) => token_ids.iter().for_each(|token_id| self.held.hold_nft(&token, token_id)),
+            _ => panic_str("Held: either amount or token ids are required"),
+        }
+
+        self.held.seeded.insert(&token);
+    }
+
+    pub fn is_held_seeded(&self, token: AccountId) -> bool {
+        self.held.is_seeded(&token)
+    }
+}
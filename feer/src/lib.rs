@@ -5,22 +5,25 @@ use near_sdk::env::panic_str;
 use near_sdk::json_types::U128;
 
 use deposit_operation::DepositOperation;
-use shared::{GAS_FOR_TX, Hashes, TransferLog, TokenType};
+use shared::{AddressFormat, AuthorizedOperation, ChainInfo, Chains, GAS_FOR_TX, Hash, Hashes, RecoverableSignature, SignerSet, TransferLog, TokenType};
 
 use crate::deposits::DepositsManager;
 use crate::external::*;
 use crate::fee_tokens::{FeeToken, FeeTokensManager};
+use crate::held::HeldTokens;
 use crate::types::{DepositLog, TransferType};
 use crate::receivers::{is_deposit_log_valid};
 
 mod fee_tokens;
 mod external;
-mod merkle;
 mod types;
 mod deposits;
+mod events;
+mod held;
 mod receivers;
 mod storage;
 mod deposit_operation;
+mod rescue;
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
@@ -32,6 +35,42 @@ pub struct Feer {
     pub deposit_operations: DepositsManager,
     /// The storage size in bytes for one operation.
     pub operations_storage_usage: StorageUsage,
+    /// Deposits are not accepted while the bridge is paused.
+    pub paused: bool,
+    /// Supported destination chains, kept in sync by the bridge. While it is empty, as after the
+    /// migration, the destination chain is not checked.
+    pub chains: Chains,
+    pub held: HeldTokens,
+    /// Nonce of the operations authorized by the bridge signers.
+    pub nonce: u128,
+}
+
+/// Layout of the fee contract state before the pause flag, read once by `migrate`.
+#[derive(BorshDeserialize)]
+pub struct OldFeer {
+    pub chain: String,
+    pub bridge_addr: AccountId,
+    pub hashes: Hashes,
+    pub tokens: FeeTokensManager,
+    pub deposit_operations: DepositsManager,
+    pub operations_storage_usage: StorageUsage,
+}
+
+impl From<OldFeer> for Feer {
+    fn from(old: OldFeer) -> Self {
+        Self {
+            chain: old.chain,
+            bridge_addr: old.bridge_addr,
+            hashes: old.hashes,
+            tokens: old.tokens,
+            deposit_operations: old.deposit_operations,
+            operations_storage_usage: old.operations_storage_usage,
+            paused: false,
+            chains: Chains::new(),
+            held: HeldTokens::unseeded(),
+            nonce: 0,
+        }
+    }
 }
 
 #[near_bindgen]
@@ -49,17 +88,59 @@ impl Feer {
             tokens: FeeTokensManager::new(tokens),
             deposit_operations: DepositsManager::new(),
             operations_storage_usage: 0,
+            paused: false,
+            chains: Chains::new(),
+            held: HeldTokens::new(),
+            nonce: 0,
         };
 
         this.measure_operation_storage_usage();
         this
     }
 
+    /// Converts the state left by the previous code, the state of the current layout is kept as is.
     #[init(ignore_state)]
     #[private]
     pub fn migrate() -> Self {
-        let contract: Feer = env::state_read().expect("ERROR, NOT INITIALIZED");
-        contract
+        let state = env::storage_read(b"STATE").expect("ERROR, NOT INITIALIZED");
+
+        if let Ok(contract) = Feer::try_from_slice(&state) {
+            return contract;
+        }
+
+        let old = OldFeer::try_from_slice(&state).unwrap_or_else(|_| env::panic_str("ERROR, UNKNOWN STATE LAYOUT"));
+        Feer::from(old)
+    }
+
+    /// Called by the bridge when it is paused or resumed.
+    pub fn set_paused(&mut self, paused: bool) {
+        assert_eq!(env::predecessor_account_id(), self.bridge_addr.clone(), "Sender must be bridge contract");
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Called by the bridge when a destination chain is added, updated or removed.
+    pub fn set_chain(&mut self, chain: String, format: Option<AddressFormat>) {
+        assert_eq!(env::predecessor_account_id(), self.bridge_addr.clone(), "Sender must be bridge contract");
+        self.chains.set_chain(&chain, format);
+    }
+
+    pub fn get_chains(&self) -> Vec<ChainInfo> {
+        self.chains.get_chains()
+    }
+
+    /// Hash of the operation bound to this contract, chain and the current nonce.
+    pub(crate) fn get_authorized_operation_hash(&self, operation: AuthorizedOperation) -> Hash {
+        operation.hash(&env::current_account_id(), &self.chain, self.nonce)
+    }
+
+    /// Verifies the signatures of the bridge signer set, the nonce makes every signature single-use.
+    pub(crate) fn check_signature_and_increment_nonce(&mut self, signers: SignerSet, msg: Hash, signatures: &[RecoverableSignature]) {
+        signers.verify(msg, signatures);
+        self.nonce += 1;
     }
 
     fn measure_operation_storage_usage(&mut self) {
@@ -70,7 +151,6 @@ impl Feer {
             sender: AccountId::new_unchecked("x".repeat(64)),
             receiver: "b".repeat(64),
             chain_to: "Near".to_string(),
-            is_wrapped: false,
             bundle_data: None,
             bundle_salt: None,
         };
@@ -99,9 +179,13 @@ impl Feer {
         &mut self,
         deposit: DepositLog,
     ) {
+        if self.paused {
+            panic_str("Deposits are paused");
+        }
+
         let deposit_amount = env::attached_deposit();
         let sender = env::predecessor_account_id();
-        let is_valid = is_deposit_log_valid(deposit.clone(), TokenType::Native, None);
+        let is_valid = is_deposit_log_valid(&self.chains, deposit.clone(), TokenType::Native, None);
 
         if !is_valid {
             panic_str("Invalid deposit log");
@@ -223,12 +307,16 @@ impl Feer {
                     );
             }
             TokenType::FT => {
+                self.held.release_ft(op.token_addr.as_ref().unwrap(), op.amount.unwrap().0);
+
                 ext_fungible_token::ext(op.token_addr.clone().unwrap())
                     .with_static_gas(GAS_FOR_TX)
                     .with_attached_deposit(ONE_YOCTO)
                     .ft_transfer_call(self.bridge_addr.clone(), op.amount.clone().unwrap(), None, op.msg.clone().unwrap());
             }
             TokenType::NFT => {
+                self.held.release_nft(op.token_addr.as_ref().unwrap(), op.token_id.as_ref().unwrap());
+
                 ext_non_fungible_token::ext(op.token_addr.clone().unwrap())
                     .with_static_gas(GAS_FOR_TX)
                     .with_attached_deposit(ONE_YOCTO)
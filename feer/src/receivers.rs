@@ -3,6 +3,8 @@ use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
 use near_sdk::{AccountId, near_bindgen, PromiseOrValue, serde_json};
 use near_sdk::json_types::U128;
 
+use shared::Chains;
+
 use super::*;
 
 #[near_bindgen]
@@ -15,8 +17,13 @@ impl NonFungibleTokenReceiver for Feer {
         token_id: TokenId,
         msg: String,
     ) -> PromiseOrValue<bool> {
+        if self.paused {
+            log!("Deposits are paused");
+            return PromiseOrValue::Value(true);
+        }
+
         let log: DepositLog = serde_json::from_str(&msg).unwrap();
-        let is_valid = is_deposit_log_valid(log.clone(), TokenType::NFT, Some(env::predecessor_account_id()));
+        let is_valid = is_deposit_log_valid(&self.chains, log.clone(), TokenType::NFT, Some(env::predecessor_account_id()));
 
         if !is_valid {
             log!("Invalid log");
@@ -34,6 +41,8 @@ impl NonFungibleTokenReceiver for Feer {
             return PromiseOrValue::Value(true);
         }
 
+        self.held.hold_nft(&env::predecessor_account_id(), &token_id);
+
         let op = self.deposit_operations.get_deposit_op(sender_id.clone()).unwrap().clone();
 
         if !(op.fee_charged && op.deposited) {
@@ -57,8 +66,13 @@ impl FungibleTokenReceiver for Feer {
         amount: U128,
         msg: String,
     ) -> PromiseOrValue<U128> {
+        if self.paused {
+            log!("Deposits are paused");
+            return PromiseOrValue::Value(amount.clone());
+        }
+
         let log: DepositLog = serde_json::from_str(&msg).unwrap();
-        let is_valid = is_deposit_log_valid(log.clone(), TokenType::FT, Some(env::predecessor_account_id()));
+        let is_valid = is_deposit_log_valid(&self.chains, log.clone(), TokenType::FT, Some(env::predecessor_account_id()));
 
         if !is_valid {
             log!("Invalid log");
@@ -76,6 +90,8 @@ impl FungibleTokenReceiver for Feer {
             return PromiseOrValue::Value(amount.clone());
         }
 
+        self.held.hold_ft(&env::predecessor_account_id(), amount.0);
+
         let op = self.deposit_operations.get_deposit_op(sender_id.clone()).unwrap().clone();
 
         if !(op.fee_charged && op.deposited) {
@@ -89,7 +105,7 @@ impl FungibleTokenReceiver for Feer {
     }
 }
 
-pub fn is_deposit_log_valid(log: DepositLog, token_type: TokenType, token: Option<AccountId>) -> bool {
+pub fn is_deposit_log_valid(chains: &Chains, log: DepositLog, token_type: TokenType, token: Option<AccountId>) -> bool {
     match log.transfer_type {
         TransferType::Fee => {
             let fee_token = log.fee_token_addr.clone();
@@ -141,5 +157,11 @@ pub fn is_deposit_log_valid(log: DepositLog, token_type: TokenType, token: Optio
         return false;
     }
 
+    // The registry of a migrated contract is empty until the bridge syncs its chains
+    if !chains.is_empty() && chains.normalize_receiver(&log.chain_to, &log.receiver).is_none() {
+        log!("Receiver is not valid for the destination chain or the chain is not supported");
+        return false;
+    }
+
     return true;
 }
@@ -0,0 +1,126 @@
+use near_contract_standards::non_fungible_token::{Token, TokenId};
+use near_sdk::{AccountId, env, Gas, near_bindgen, ONE_YOCTO, Promise, PromiseError, require};
+use near_sdk::env::panic_str;
+use near_sdk::json_types::U128;
+
+use shared::{AuthorizedOperation, GAS_FOR_TX, OperationType, RecoverableSignature, SignerSet};
+
+use crate::deposit_operation::unwrap_signer_set;
+use crate::events::emit_tokens_rescued;
+
+use super::*;
+
+#[near_bindgen]
+impl Feer {
+    /// Returns the tokens sent to the contract with a plain `ft_transfer` / `nft_transfer`. Either
+    /// `amount` of the fungible token or `token_id` of the non-fungible token is rescued, the
+    /// collected fees and the deposits in progress can not be touched. Signed by the bridge signers
+    /// with the same operation as the bridge `rescue_tokens`. Tokens held before the upgrade must be
+    /// seeded with `seed_held_tokens` first.
+    pub fn rescue_tokens(
+        &mut self,
+        token: AccountId,
+        amount: Option<U128>,
+        token_id: Option<TokenId>,
+        receiver: AccountId,
+        signatures: Vec<RecoverableSignature>,
+    ) -> Promise {
+        require!(self.held.is_seeded(&token), "Rescue: held tokens are not seeded");
+
+        ext_bridge::ext(self.bridge_addr.clone()).get_signers()
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas(GAS_FOR_TX.0 * 5))
+                    .rescue_tokens_callback(token, amount, token_id, receiver, signatures)
+            )
+    }
+
+    #[private]
+    pub fn rescue_tokens_callback(
+        &mut self,
+        #[callback_result] call_result: Result<SignerSet, PromiseError>,
+        token: AccountId,
+        amount: Option<U128>,
+        token_id: Option<TokenId>,
+        receiver: AccountId,
+        signatures: Vec<RecoverableSignature>,
+    ) {
+        let hash = self.get_authorized_operation_hash(
+            AuthorizedOperation::new(OperationType::RescueTokens)
+                .account(&token)
+                .borsh(&amount)
+                .borsh(&token_id)
+                .account(&receiver)
+        );
+        self.check_signature_and_increment_nonce(unwrap_signer_set(call_result), hash, &signatures);
+
+        match (amount, token_id) {
+            (Some(amount), None) => {
+                require!(amount.0 > 0, "Rescue: amount must be positive");
+
+                ext_fungible_token::ext(token.clone())
+                    .with_static_gas(GAS_FOR_TX)
+                    .ft_balance_of(env::current_account_id())
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(GAS_FOR_TX + GAS_FOR_TX)
+                            .rescue_ft_callback(token, amount, receiver)
+                    );
+            }
+            (None, Some(token_id)) => {
+                ext_non_fungible_token::ext(token.clone())
+                    .with_static_gas(GAS_FOR_TX)
+                    .nft_token(token_id.clone())
+                    .then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(GAS_FOR_TX + GAS_FOR_TX)
+                            .rescue_nft_callback(token, token_id, receiver)
+                    );
+            }
+            _ => panic_str("Rescue: either amount or token id is required"),
+        }
+    }
+
+    #[private]
+    pub fn rescue_ft_callback(
+        &mut self,
+        #[callback_result] balance: Result<U128, PromiseError>,
+        token: AccountId,
+        amount: U128,
+        receiver: AccountId,
+    ) {
+        let balance = balance.unwrap_or_else(|_| panic_str("Rescue: failed to get token balance"));
+        let excess = balance.0.saturating_sub(self.held.get_ft(&token));
+        require!(amount.0 <= excess, format!("Rescue: only {} tokens are not held", excess));
+
+        ext_fungible_token::ext(token.clone())
+            .with_static_gas(GAS_FOR_TX)
+            .with_attached_deposit(ONE_YOCTO)
+            .ft_transfer(receiver.clone(), amount, None);
+
+        emit_tokens_rescued(&token, None, Some(&amount.0.to_string()), &receiver);
+    }
+
+    #[private]
+    pub fn rescue_nft_callback(
+        &mut self,
+        #[callback_result] owned: Result<Option<Token>, PromiseError>,
+        token: AccountId,
+        token_id: TokenId,
+        receiver: AccountId,
+    ) {
+        let owned = owned.unwrap_or_else(|_| panic_str("Rescue: failed to get token"));
+        require!(
+            owned.map_or(false, |item| item.owner_id == env::current_account_id()),
+            "Rescue: token is not owned by the contract",
+        );
+        require!(!self.held.is_nft_held(&token, &token_id), "Rescue: token is held for a deposit");
+
+        ext_non_fungible_token::ext(token.clone())
+            .with_static_gas(GAS_FOR_TX)
+            .with_attached_deposit(ONE_YOCTO)
+            .nft_transfer(receiver.clone(), token_id.clone(), None, None);
+
+        emit_tokens_rescued(&token, Some(token_id.as_str()), None, &receiver);
+    }
+}
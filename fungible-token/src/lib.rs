@@ -19,17 +19,47 @@ use near_contract_standards::fungible_token::FungibleToken;
 use near_contract_standards::fungible_token::metadata::{
     FT_METADATA_SPEC, FungibleTokenMetadata, FungibleTokenMetadataProvider,
 };
-use near_sdk::{AccountId, Balance, env, log, near_bindgen, PanicOnDefault, PromiseOrValue};
+use near_sdk::{AccountId, assert_one_yocto, Balance, env, log, near_bindgen, PanicOnDefault, Promise, PromiseOrValue};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LazyOption;
+use near_sdk::collections::{LazyOption, UnorderedMap};
 use near_sdk::json_types::U128;
 
+use crate::roles::Minter;
+
+mod events;
+mod roles;
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
     token: FungibleToken,
     metadata: LazyOption<FungibleTokenMetadata>,
     owner_id: AccountId,
+    // Set while the ownership transfer is not accepted
+    pending_owner_id: Option<AccountId>,
+    minters: UnorderedMap<AccountId, Minter>,
+    max_supply: Option<Balance>,
+}
+
+/// Layout of the token state before the ownership transfer and the minters, read once by `migrate`.
+#[derive(BorshDeserialize)]
+pub struct OldContract {
+    token: FungibleToken,
+    metadata: LazyOption<FungibleTokenMetadata>,
+    owner_id: AccountId,
+}
+
+impl From<OldContract> for Contract {
+    fn from(old: OldContract) -> Self {
+        Self {
+            token: old.token,
+            metadata: old.metadata,
+            owner_id: old.owner_id,
+            pending_owner_id: None,
+            minters: UnorderedMap::new(b"n".to_vec()),
+            max_supply: None,
+        }
+    }
 }
 
 const DEFAULT_TOKEN_LOGO: &str = "data:image/svg+xml;charset=UTF-8,%3Csvg version='1.0' xmlns='http://www.w3.org/2000/svg' width='512.000000pt' height='512.000000pt' viewBox='0 0 512.000000 512.000000' preserveAspectRatio='xMidYMid meet'%3E%3Cg transform='translate(0.000000,512.000000) scale(0.100000,-0.100000)'%0Afill='%23000000' stroke='none'%3E%3Cpath d='M2567 2912 c-22 -24 -21 -55 1 -75 37 -33 92 -11 92 37 0 48 -62 73%0A-93 38z'/%3E%3Cpath d='M1068 2904 c-5 -4 -8 -162 -8 -351 l0 -343 45 0 45 0 0 135 0 136 86%0A-3 87 -3 71 -132 71 -133 48 0 c26 0 47 2 47 5 0 3 -34 67 -75 142 l-74 137%0A27 17 c71 43 109 125 97 213 -10 80 -41 123 -115 162 -28 15 -64 19 -190 22%0A-85 2 -158 0 -162 -4z m317 -88 c42 -18 65 -61 65 -123 0 -104 -44 -133 -197%0A-133 l-103 0 0 135 0 135 100 0 c64 0 113 -5 135 -14z'/%3E%3Cpath d='M1776 2726 c-39 -15 -90 -57 -107 -89 -8 -15 -4 -20 25 -32 34 -14%0A35 -14 68 19 80 80 218 49 218 -50 0 -18 -4 -36 -10 -39 -5 -3 -54 -13 -107%0A-21 -170 -25 -234 -78 -218 -179 12 -84 79 -135 175 -135 62 0 110 19 138 55%0Al22 28 0 -37 c0 -35 1 -36 41 -36 l40 0 -3 214 c-3 200 -4 217 -24 243 -12 15%0A-36 37 -53 47 -43 25 -153 31 -205 12z m187 -388 c-48 -82 -194 -92 -231 -16%0A-16 34 -6 75 23 94 13 9 65 22 117 30 51 9 95 18 98 21 3 2 6 -18 8 -47 2 -36%0A-2 -61 -15 -82z'/%3E%3Cpath d='M2354 2721 c-18 -11 -39 -30 -48 -42 -16 -23 -16 -23 -16 14 0 37 0%0A37 -40 37 l-40 0 0 -260 0 -260 40 0 40 0 0 180 c0 200 6 227 60 255 16 8 49%0A15 75 15 l45 0 0 40 0 40 -42 0 c-25 0 -57 -8 -74 -19z'/%3E%3Cpath d='M2944 2721 c-17 -10 -40 -31 -52 -46 l-22 -28 0 42 0 41 -40 0 -40 0%0A0 -260 0 -260 39 0 39 0 4 183 c3 206 11 232 74 262 45 21 92 14 125 -21 23%0A-25 24 -30 27 -225 l3 -199 40 0 39 0 0 196 0 196 29 28 c36 37 72 46 122 31%0A63 -19 69 -40 69 -261 l0 -190 40 0 40 0 0 203 c0 191 -1 205 -22 247 -51 98%0A-196 110 -270 22 l-25 -30 -23 30 c-42 56 -136 75 -196 39z'/%3E%3Cpath d='M3755 2725 c-54 -19 -118 -91 -136 -152 -17 -57 -17 -152 -1 -209 15%0A-53 76 -122 126 -145 98 -45 227 -9 285 78 73 110 63 282 -22 372 -63 66 -161%0A88 -252 56z m183 -97 c90 -88 74 -284 -28 -340 -41 -23 -114 -19 -154 7 -59%0A39 -88 175 -57 266 13 39 52 82 91 98 44 19 110 4 148 -31z'/%3E%3Cpath d='M2570 2470 l0 -260 40 0 40 0 0 260 0 260 -40 0 -40 0 0 -260z'/%3E%3C/g%3E%3C/svg%3E%0A";
@@ -73,11 +103,11 @@ impl Contract {
             token: FungibleToken::new(b"a".to_vec()),
             metadata: LazyOption::new(b"m".to_vec(), Some(&metadata)),
             owner_id: owner_id.clone(),
+            pending_owner_id: None,
+            minters: UnorderedMap::new(b"n".to_vec()),
+            max_supply: None,
         };
 
-        // Need to register system account to make possible to burn tokens
-        this.token.internal_register_account(&AccountId::new_unchecked("system".to_string()));
-
         this.token.internal_register_account(&owner_id);
         this.token.internal_deposit(&owner_id, total_supply.into());
         near_contract_standards::fungible_token::events::FtMint {
@@ -89,16 +119,29 @@ impl Contract {
         this
     }
 
-    #[payable]
-    pub fn ft_mint(&mut self, receiver_id: AccountId, amount: U128) {
-        assert_eq!(env::predecessor_account_id(), self.owner_id, "Unauthorized: Only owner can mint");
-
-        let balance =  self.token.accounts.get(&receiver_id);
+    /// Converts the state left by the previous code, the state of the current layout is kept as is.
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate() -> Self {
+        let state = env::storage_read(b"STATE").expect("ERROR, NOT INITIALIZED");
 
-        if balance.is_none() {
-            self.token.internal_register_account(&receiver_id);
+        if let Ok(contract) = Contract::try_from_slice(&state) {
+            return contract;
         }
 
+        let old = OldContract::try_from_slice(&state).unwrap_or_else(|_| env::panic_str("ERROR, UNKNOWN STATE LAYOUT"));
+        Contract::from(old)
+    }
+
+    /// Mints tokens to `receiver_id`, registering the account if needed. Can be called by the
+    /// owner or a minter within its cap and the max supply. The attached deposit has to cover the
+    /// storage used by the registration, the surplus is refunded to `refund_id` or to the caller if
+    /// it is not set.
+    #[payable]
+    pub fn ft_mint(&mut self, receiver_id: AccountId, amount: U128, refund_id: Option<AccountId>) {
+        self.internal_use_mint_allowance(amount.into());
+        self.internal_register_with_refund(&receiver_id, refund_id);
+
         self.token.internal_deposit(&receiver_id, amount.into());
         near_contract_standards::fungible_token::events::FtMint {
             owner_id: &receiver_id,
@@ -108,6 +151,45 @@ impl Contract {
             .emit();
     }
 
+    /// Burns `amount` tokens of the owner, the total supply goes down by the same amount.
+    #[payable]
+    pub fn ft_burn(&mut self, amount: U128) {
+        assert_one_yocto();
+        assert_eq!(env::predecessor_account_id(), self.owner_id, "Unauthorized: Only owner can burn");
+
+        self.token.internal_withdraw(&self.owner_id, amount.into());
+        near_contract_standards::fungible_token::events::FtBurn {
+            owner_id: &self.owner_id,
+            amount: &amount,
+            memo: None,
+        }
+            .emit();
+    }
+
+    /// Registers the account if needed, the attached deposit has to cover the storage used by the
+    /// registration. The surplus is refunded to `refund_id` or to the caller if it is not set.
+    fn internal_register_with_refund(&mut self, account_id: &AccountId, refund_id: Option<AccountId>) {
+        let initial_storage_usage = env::storage_usage();
+        let balance =  self.token.accounts.get(account_id);
+
+        if balance.is_none() {
+            self.token.internal_register_account(account_id);
+        }
+
+        let storage_cost = Balance::from(env::storage_usage() - initial_storage_usage) * env::storage_byte_cost();
+        let deposit = env::attached_deposit();
+        assert!(
+            deposit >= storage_cost,
+            "Must attach {} yoctoNEAR to cover storage",
+            storage_cost,
+        );
+
+        let refund = deposit - storage_cost;
+        if refund > 0 {
+            Promise::new(refund_id.unwrap_or_else(env::predecessor_account_id)).transfer(refund);
+        }
+    }
+
     fn on_account_closed(&mut self, account_id: AccountId, balance: Balance) {
         log!("Closed @{} with {}", account_id, balance);
     }
@@ -194,4 +276,266 @@ mod tests {
         assert_eq!(contract.ft_balance_of(accounts(2)).0, (TOTAL_SUPPLY - transfer_amount));
         assert_eq!(contract.ft_balance_of(accounts(1)).0, transfer_amount);
     }
+
+    #[test]
+    fn test_mint() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(contract.storage_balance_bounds().min.into())
+            .build());
+        // The receiver is registered by the mint
+        let mint_amount = TOTAL_SUPPLY / 3;
+        contract.ft_mint(accounts(1), mint_amount.into(), None);
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .account_balance(env::account_balance())
+            .is_view(true)
+            .attached_deposit(0)
+            .build());
+        assert_eq!(contract.ft_total_supply().0, TOTAL_SUPPLY + mint_amount);
+        assert_eq!(contract.ft_balance_of(accounts(1)).0, mint_amount);
+    }
+
+    #[test]
+    fn test_burn() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        let burn_amount = TOTAL_SUPPLY / 3;
+        contract.ft_burn(burn_amount.into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .account_balance(env::account_balance())
+            .is_view(true)
+            .attached_deposit(0)
+            .build());
+        assert_eq!(contract.ft_total_supply().0, TOTAL_SUPPLY - burn_amount);
+        assert_eq!(contract.ft_balance_of(accounts(2)).0, TOTAL_SUPPLY - burn_amount);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: Only owner can burn")]
+    fn test_burn_not_owner() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(accounts(1))
+            .build());
+        contract.ft_burn((TOTAL_SUPPLY / 3).into());
+    }
+
+    #[test]
+    fn test_minter_mint() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        contract.add_minter(accounts(3), Some(U128(100)));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(contract.storage_balance_bounds().min.into())
+            .predecessor_account_id(accounts(3))
+            .build());
+        contract.ft_mint(accounts(1), U128(60), None);
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .account_balance(env::account_balance())
+            .is_view(true)
+            .attached_deposit(0)
+            .build());
+        assert_eq!(contract.ft_balance_of(accounts(1)).0, 60);
+        assert_eq!(contract.get_minter(accounts(3)).unwrap().minted, U128(60));
+    }
+
+    #[test]
+    #[should_panic(expected = "Minter cap exceeded")]
+    fn test_minter_cap_exceeded() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        contract.add_minter(accounts(3), Some(U128(100)));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(0)
+            .predecessor_account_id(accounts(3))
+            .build());
+        contract.ft_mint(accounts(2), U128(101), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: Only owner or minter can mint")]
+    fn test_removed_minter_mint() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        contract.add_minter(accounts(3), None);
+        contract.remove_minter(accounts(3));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(0)
+            .predecessor_account_id(accounts(3))
+            .build());
+        contract.ft_mint(accounts(2), U128(1), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Max supply exceeded")]
+    fn test_max_supply_exceeded() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        contract.set_max_supply(Some(U128(TOTAL_SUPPLY + 10)));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(0)
+            .build());
+        contract.ft_mint(accounts(2), U128(11), None);
+    }
+
+    #[test]
+    fn test_transfer_ownership() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        contract.transfer_ownership(accounts(3));
+        assert_eq!(contract.get_owner(), accounts(2));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(contract.storage_balance_bounds().min.into())
+            .predecessor_account_id(accounts(3))
+            .build());
+        contract.storage_deposit(None, None);
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(accounts(3))
+            .build());
+        contract.accept_ownership();
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .account_balance(env::account_balance())
+            .is_view(true)
+            .attached_deposit(0)
+            .build());
+        assert_eq!(contract.get_owner(), accounts(3));
+        assert_eq!(contract.get_pending_owner(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: Only pending owner can accept ownership")]
+    fn test_accept_ownership_not_pending() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        contract.transfer_ownership(accounts(3));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .predecessor_account_id(accounts(1))
+            .build());
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "Pending owner is not registered")]
+    fn test_accept_ownership_not_registered() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        contract.transfer_ownership(accounts(3));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .predecessor_account_id(accounts(3))
+            .build());
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "Must attach")]
+    fn test_mint_without_storage_deposit() {
+        let mut context = get_context(accounts(2));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(2).into(), TOTAL_SUPPLY.into());
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(0)
+            .build());
+        contract.ft_mint(accounts(1), (TOTAL_SUPPLY / 3).into(), None);
+    }
+
+    #[test]
+    fn test_migrate_old_state() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut token = FungibleToken::new(b"a".to_vec());
+        token.internal_register_account(&accounts(1));
+        token.internal_deposit(&accounts(1), TOTAL_SUPPLY);
+        let metadata = FungibleTokenMetadata {
+            spec: FT_METADATA_SPEC.to_string(),
+            name: "Old token".to_string(),
+            symbol: "OLD".to_string(),
+            icon: None,
+            reference: None,
+            reference_hash: None,
+            decimals: 18,
+        };
+        let state = (token, LazyOption::new(b"m".to_vec(), Some(&metadata)), accounts(1));
+        env::storage_write(b"STATE", &state.try_to_vec().unwrap());
+
+        let contract = Contract::migrate();
+        assert_eq!(contract.ft_total_supply().0, TOTAL_SUPPLY);
+        assert_eq!(contract.ft_balance_of(accounts(1)).0, TOTAL_SUPPLY);
+        assert_eq!(contract.ft_metadata().symbol, "OLD");
+        assert_eq!(contract.owner_id, accounts(1));
+        assert!(contract.pending_owner_id.is_none());
+        assert!(contract.max_supply.is_none());
+    }
 }
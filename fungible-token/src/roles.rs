@@ -0,0 +1,145 @@
+use near_sdk::{AccountId, assert_one_yocto, env, near_bindgen, require};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::json;
+
+use crate::*;
+use crate::events::emit_role_event;
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Minter {
+    pub account_id: AccountId,
+    // Maximal amount the minter can mint, unlimited if not set
+    pub cap: Option<U128>,
+    pub minted: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner_id.clone()
+    }
+
+    /// Starts the ownership transfer, it is completed when the new owner calls `accept_ownership`.
+    #[payable]
+    pub fn transfer_ownership(&mut self, new_owner_id: AccountId) {
+        self.internal_assert_owner();
+
+        self.pending_owner_id = Some(new_owner_id.clone());
+        emit_role_event("ownership_transfer_started", json!({
+            "owner_id": self.owner_id,
+            "pending_owner_id": new_owner_id,
+        }));
+    }
+
+    /// Completes the ownership transfer. The new owner has to be registered with `storage_deposit`
+    /// beforehand, deposits are burned from its balance.
+    #[payable]
+    pub fn accept_ownership(&mut self) {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        require!(self.pending_owner_id.as_ref() == Some(&owner_id), "Unauthorized: Only pending owner can accept ownership");
+        require!(self.token.accounts.contains_key(&owner_id), "Pending owner is not registered");
+
+        let previous_owner_id = std::mem::replace(&mut self.owner_id, owner_id.clone());
+        self.pending_owner_id = None;
+        emit_role_event("ownership_transferred", json!({
+            "previous_owner_id": previous_owner_id,
+            "owner_id": owner_id,
+        }));
+    }
+
+    /// Adds the minter or updates its cap, the minted amount is kept.
+    #[payable]
+    pub fn add_minter(&mut self, account_id: AccountId, cap: Option<U128>) {
+        self.internal_assert_owner();
+
+        let minted = self.minters.get(&account_id).map_or(U128(0), |minter| minter.minted);
+        self.minters.insert(&account_id, &Minter {
+            account_id: account_id.clone(),
+            cap,
+            minted,
+        });
+        emit_role_event("minter_added", json!({
+            "account_id": account_id,
+            "cap": cap,
+        }));
+    }
+
+    #[payable]
+    pub fn remove_minter(&mut self, account_id: AccountId) {
+        self.internal_assert_owner();
+
+        require!(self.minters.remove(&account_id).is_some(), "Minter not found");
+        emit_role_event("minter_removed", json!({
+            "account_id": account_id,
+        }));
+    }
+
+    pub fn get_minter(&self, account_id: AccountId) -> Option<Minter> {
+        self.minters.get(&account_id)
+    }
+
+    pub fn get_minters(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<Minter> {
+        self.minters.values()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u64::MAX) as usize)
+            .collect()
+    }
+
+    /// Sets the cap on the total supply, `None` removes it.
+    #[payable]
+    pub fn set_max_supply(&mut self, max_supply: Option<U128>) {
+        self.internal_assert_owner();
+
+        if let Some(max_supply) = max_supply {
+            require!(max_supply.0 >= self.token.total_supply, "Max supply is less than the total supply");
+        }
+
+        self.max_supply = max_supply.map(|max_supply| max_supply.0);
+        emit_role_event("max_supply_updated", json!({
+            "max_supply": max_supply,
+        }));
+    }
+
+    pub fn get_max_supply(&self) -> Option<U128> {
+        self.max_supply.map(U128)
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_assert_owner(&self) {
+        assert_one_yocto();
+        require!(env::predecessor_account_id() == self.owner_id, "Unauthorized: Only owner can manage roles");
+    }
+
+    /// Checks that the caller can mint the amount and records it for the minter. The owner is not
+    /// limited by a minter cap, the max supply applies to everyone.
+    pub(crate) fn internal_use_mint_allowance(&mut self, amount: Balance) {
+        let account_id = env::predecessor_account_id();
+
+        if account_id != self.owner_id {
+            let minter = self.minters.get(&account_id);
+            require!(minter.is_some(), "Unauthorized: Only owner or minter can mint");
+
+            let mut minter = minter.unwrap();
+            let minted = minter.minted.0 + amount;
+            if let Some(cap) = minter.cap {
+                require!(minted <= cap.0, "Minter cap exceeded");
+            }
+
+            minter.minted = U128(minted);
+            self.minters.insert(&account_id, &minter);
+        }
+
+        if let Some(max_supply) = self.max_supply {
+            require!(self.token.total_supply + amount <= max_supply, "Max supply exceeded");
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use near_sdk::env;
+use near_sdk::serde_json::{json, Value};
+
+/// Logs a NEP-297 event of the token access control.
+pub(crate) fn emit_role_event(event: &str, data: Value) {
+    let event = json!({
+        "standard": "nft_roles",
+        "version": "1.0.0",
+        "event": event,
+        "data": [data],
+    });
+
+    env::log_str(&format!("EVENT_JSON:{}", event));
+}
@@ -16,22 +16,53 @@ NOTES:
     keys on its account.
  */
 
+use std::collections::HashMap;
+
 use near_contract_standards::non_fungible_token::{Token, TokenId};
 use near_contract_standards::non_fungible_token::metadata::{
     NFT_METADATA_SPEC, NFTContractMetadata, NonFungibleTokenMetadataProvider, TokenMetadata,
 };
-use near_contract_standards::non_fungible_token::NonFungibleToken;
+use near_contract_standards::non_fungible_token::{NonFungibleToken, refund_deposit_to_account};
 use near_sdk::{
-    AccountId, BorshStorageKey, env, near_bindgen, PanicOnDefault, Promise, PromiseOrValue,
+    AccountId, assert_one_yocto, BorshStorageKey, env, near_bindgen, PanicOnDefault, Promise, PromiseOrValue,
 };
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LazyOption;
+use near_sdk::collections::{LazyOption, LookupMap, UnorderedSet};
+
+mod events;
+mod roles;
+mod royalties;
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
     tokens: NonFungibleToken,
     metadata: LazyOption<NFTContractMetadata>,
+    // Set while the ownership transfer is not accepted
+    pending_owner_id: Option<AccountId>,
+    minters: UnorderedSet<AccountId>,
+    // Royalty shares of the tokens in basis points
+    royalties: LookupMap<TokenId, HashMap<AccountId, u32>>,
+}
+
+/// Layout of the token state before the ownership transfer, the minters and the royalties, read
+/// once by `migrate`.
+#[derive(BorshDeserialize)]
+pub struct OldContract {
+    tokens: NonFungibleToken,
+    metadata: LazyOption<NFTContractMetadata>,
+}
+
+impl From<OldContract> for Contract {
+    fn from(old: OldContract) -> Self {
+        Self {
+            tokens: old.tokens,
+            metadata: old.metadata,
+            pending_owner_id: None,
+            minters: UnorderedSet::new(StorageKey::Minters),
+            royalties: LookupMap::new(StorageKey::Royalties),
+        }
+    }
 }
 
 #[derive(BorshSerialize, BorshStorageKey)]
@@ -41,6 +72,8 @@ enum StorageKey {
     TokenMetadata,
     Enumeration,
     Approval,
+    Minters,
+    Royalties,
 }
 
 const DEFAULT_TOKEN_LOGO: &str = "data:image/svg+xml;charset=UTF-8,%3Csvg version='1.0' xmlns='http://www.w3.org/2000/svg' width='512.000000pt' height='512.000000pt' viewBox='0 0 512.000000 512.000000' preserveAspectRatio='xMidYMid meet'%3E%3Cg transform='translate(0.000000,512.000000) scale(0.100000,-0.100000)'%0Afill='%23000000' stroke='none'%3E%3Cpath d='M2567 2912 c-22 -24 -21 -55 1 -75 37 -33 92 -11 92 37 0 48 -62 73%0A-93 38z'/%3E%3Cpath d='M1068 2904 c-5 -4 -8 -162 -8 -351 l0 -343 45 0 45 0 0 135 0 136 86%0A-3 87 -3 71 -132 71 -133 48 0 c26 0 47 2 47 5 0 3 -34 67 -75 142 l-74 137%0A27 17 c71 43 109 125 97 213 -10 80 -41 123 -115 162 -28 15 -64 19 -190 22%0A-85 2 -158 0 -162 -4z m317 -88 c42 -18 65 -61 65 -123 0 -104 -44 -133 -197%0A-133 l-103 0 0 135 0 135 100 0 c64 0 113 -5 135 -14z'/%3E%3Cpath d='M1776 2726 c-39 -15 -90 -57 -107 -89 -8 -15 -4 -20 25 -32 34 -14%0A35 -14 68 19 80 80 218 49 218 -50 0 -18 -4 -36 -10 -39 -5 -3 -54 -13 -107%0A-21 -170 -25 -234 -78 -218 -179 12 -84 79 -135 175 -135 62 0 110 19 138 55%0Al22 28 0 -37 c0 -35 1 -36 41 -36 l40 0 -3 214 c-3 200 -4 217 -24 243 -12 15%0A-36 37 -53 47 -43 25 -153 31 -205 12z m187 -388 c-48 -82 -194 -92 -231 -16%0A-16 34 -6 75 23 94 13 9 65 22 117 30 51 9 95 18 98 21 3 2 6 -18 8 -47 2 -36%0A-2 -61 -15 -82z'/%3E%3Cpath d='M2354 2721 c-18 -11 -39 -30 -48 -42 -16 -23 -16 -23 -16 14 0 37 0%0A37 -40 37 l-40 0 0 -260 0 -260 40 0 40 0 0 180 c0 200 6 227 60 255 16 8 49%0A15 75 15 l45 0 0 40 0 40 -42 0 c-25 0 -57 -8 -74 -19z'/%3E%3Cpath d='M2944 2721 c-17 -10 -40 -31 -52 -46 l-22 -28 0 42 0 41 -40 0 -40 0%0A0 -260 0 -260 39 0 39 0 4 183 c3 206 11 232 74 262 45 21 92 14 125 -21 23%0A-25 24 -30 27 -225 l3 -199 40 0 39 0 0 196 0 196 29 28 c36 37 72 46 122 31%0A63 -19 69 -40 69 -261 l0 -190 40 0 40 0 0 203 c0 191 -1 205 -22 247 -51 98%0A-196 110 -270 22 l-25 -30 -23 30 c-42 56 -136 75 -196 39z'/%3E%3Cpath d='M3755 2725 c-54 -19 -118 -91 -136 -152 -17 -57 -17 -152 -1 -209 15%0A-53 76 -122 126 -145 98 -45 227 -9 285 78 73 110 63 282 -22 372 -63 66 -161%0A88 -252 56z m183 -97 c90 -88 74 -284 -28 -340 -41 -23 -114 -19 -154 7 -59%0A39 -88 175 -57 266 13 39 52 82 91 98 44 19 110 4 148 -31z'/%3E%3Cpath d='M2570 2470 l0 -260 40 0 40 0 0 260 0 260 -40 0 -40 0 0 -260z'/%3E%3C/g%3E%3C/svg%3E%0A";
@@ -83,31 +116,102 @@ impl Contract {
                 Some(StorageKey::Approval),
             ),
             metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
+            pending_owner_id: None,
+            minters: UnorderedSet::new(StorageKey::Minters),
+            royalties: LookupMap::new(StorageKey::Royalties),
         }
     }
 
+    /// Converts the state left by the previous code, the state of the current layout is kept as is.
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate() -> Self {
+        let state = env::storage_read(b"STATE").expect("ERROR, NOT INITIALIZED");
+
+        if let Ok(contract) = Contract::try_from_slice(&state) {
+            return contract;
+        }
+
+        let old = OldContract::try_from_slice(&state).unwrap_or_else(|_| env::panic_str("ERROR, UNKNOWN STATE LAYOUT"));
+        Contract::from(old)
+    }
+
     /// Mint a new token with ID=`token_id` belonging to `receiver_id`.
     ///
     /// Since this example implements metadata, it also requires per-token metadata to be provided
     /// in this call. `self.tokens.mint` will also require it to be Some, since
     /// `StorageKey::TokenMetadata` was provided at initialization.
     ///
-    /// `self.tokens.mint` will enforce `predecessor_account_id` to equal the `owner_id` given in
-    /// initialization call to `new`.
+    /// Can be called by the owner or a minter.
+    ///
+    /// `royalty` maps the accounts to their shares of the sales in basis points, it is paid out
+    /// by `nft_payout` and `nft_transfer_payout`.
+    ///
+    /// The attached deposit has to cover the storage used by the token, the surplus is refunded
+    /// to `refund_id` or to the caller if it is not set.
     #[payable]
     pub fn nft_mint(
         &mut self,
         token_id: TokenId,
         receiver_id: AccountId,
         token_metadata: TokenMetadata,
+        refund_id: Option<AccountId>,
+        royalty: Option<HashMap<AccountId, u32>>,
     ) -> Token {
-        assert_eq!(env::predecessor_account_id(), self.tokens.owner_id, "Unauthorized: Only owner can mint");
-        self.tokens.internal_mint_with_refund(
-            token_id,
-            receiver_id,
-            Some(token_metadata),
-            Some(env::signer_account_id()),
-        )
+        self.internal_assert_minter();
+
+        let initial_storage_usage = env::storage_usage();
+        let token = self.tokens.internal_mint_with_refund(token_id.clone(), receiver_id, Some(token_metadata), None);
+        if let Some(royalty) = royalty.filter(|royalty| !royalty.is_empty()) {
+            Self::internal_assert_royalty(&royalty);
+            self.royalties.insert(&token_id, &royalty);
+        }
+
+        refund_deposit_to_account(
+            env::storage_usage() - initial_storage_usage,
+            refund_id.unwrap_or_else(env::predecessor_account_id),
+        );
+        token
+    }
+
+    /// Burns the token owned by the contract owner, it is removed with its metadata and approvals.
+    #[payable]
+    pub fn nft_burn(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        assert_eq!(owner_id, self.tokens.owner_id, "Unauthorized: Only owner can burn");
+
+        let token_owner_id = self.tokens.owner_by_id.get(&token_id).unwrap_or_else(|| env::panic_str("Token not found"));
+        assert_eq!(token_owner_id, owner_id, "Token is not owned by the owner");
+
+        self.tokens.owner_by_id.remove(&token_id);
+        if let Some(token_metadata_by_id) = &mut self.tokens.token_metadata_by_id {
+            token_metadata_by_id.remove(&token_id);
+        }
+        if let Some(tokens_per_owner) = &mut self.tokens.tokens_per_owner {
+            let mut owner_tokens = tokens_per_owner.get(&owner_id).unwrap_or_else(|| env::panic_str("Token not found"));
+            owner_tokens.remove(&token_id);
+            if owner_tokens.is_empty() {
+                tokens_per_owner.remove(&owner_id);
+            } else {
+                tokens_per_owner.insert(&owner_id, &owner_tokens);
+            }
+        }
+        if let Some(approvals_by_id) = &mut self.tokens.approvals_by_id {
+            approvals_by_id.remove(&token_id);
+        }
+        if let Some(next_approval_id_by_id) = &mut self.tokens.next_approval_id_by_id {
+            next_approval_id_by_id.remove(&token_id);
+        }
+        self.royalties.remove(&token_id);
+
+        near_contract_standards::non_fungible_token::events::NftBurn {
+            owner_id: &owner_id,
+            token_ids: &[&token_id],
+            authorized_id: None,
+            memo: None,
+        }
+            .emit();
     }
 }
 
@@ -124,6 +228,7 @@ impl NonFungibleTokenMetadataProvider for Contract {
 
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
+    use near_sdk::json_types::U128;
     use near_sdk::test_utils::{accounts, VMContextBuilder};
     use near_sdk::testing_env;
     use std::collections::HashMap;
@@ -188,13 +293,339 @@ mod tests {
             .build());
 
         let token_id = "0".to_string();
-        let token = contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata());
+        let token = contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata(), None, None);
         assert_eq!(token.token_id, token_id);
         assert_eq!(token.owner_id.to_string(), accounts(0).to_string());
         assert_eq!(token.metadata.unwrap(), sample_token_metadata());
         assert_eq!(token.approved_account_ids.unwrap(), HashMap::new());
     }
 
+    #[test]
+    fn test_burn() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST)
+            .predecessor_account_id(accounts(0))
+            .build());
+        let token_id = "0".to_string();
+        contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata(), None, None);
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(accounts(0))
+            .build());
+        contract.nft_burn(token_id.clone());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .account_balance(env::account_balance())
+            .is_view(true)
+            .attached_deposit(0)
+            .build());
+        assert_eq!(contract.nft_token(token_id), None);
+        assert_eq!(contract.nft_total_supply().0, 0);
+        assert_eq!(contract.nft_supply_for_owner(accounts(0)).0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is not owned by the owner")]
+    fn test_burn_not_owned() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST)
+            .predecessor_account_id(accounts(0))
+            .build());
+        let token_id = "0".to_string();
+        contract.nft_mint(token_id.clone(), accounts(1), sample_token_metadata(), None, None);
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(accounts(0))
+            .build());
+        contract.nft_burn(token_id);
+    }
+
+    #[test]
+    fn test_payout() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST * 2)
+            .predecessor_account_id(accounts(0))
+            .build());
+        let token_id = "0".to_string();
+        let royalty = HashMap::from([(accounts(2), 500), (accounts(3), 250)]);
+        contract.nft_mint(token_id.clone(), accounts(1), sample_token_metadata(), None, Some(royalty.clone()));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .account_balance(env::account_balance())
+            .is_view(true)
+            .attached_deposit(0)
+            .build());
+        assert_eq!(contract.nft_royalties(token_id.clone()), royalty);
+
+        let payout = contract.nft_payout(token_id, U128(1000), Some(3)).payout;
+        assert_eq!(payout.len(), 3);
+        assert_eq!(payout[&accounts(1)], U128(925));
+        assert_eq!(payout[&accounts(2)], U128(50));
+        assert_eq!(payout[&accounts(3)], U128(25));
+    }
+
+    #[test]
+    #[should_panic(expected = "Royalty can not exceed 100%")]
+    fn test_mint_royalty_exceeds_total() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST * 2)
+            .predecessor_account_id(accounts(0))
+            .build());
+        let royalty = HashMap::from([(accounts(2), 6000), (accounts(3), 5000)]);
+        contract.nft_mint("0".to_string(), accounts(1), sample_token_metadata(), None, Some(royalty));
+    }
+
+    #[test]
+    #[should_panic(expected = "Payout exceeds max_len_payout")]
+    fn test_payout_exceeds_max_len() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST * 2)
+            .predecessor_account_id(accounts(0))
+            .build());
+        let token_id = "0".to_string();
+        let royalty = HashMap::from([(accounts(2), 500), (accounts(3), 250)]);
+        contract.nft_mint(token_id.clone(), accounts(1), sample_token_metadata(), None, Some(royalty));
+
+        contract.nft_payout(token_id, U128(1000), Some(2));
+    }
+
+    #[test]
+    fn test_transfer_payout() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST * 2)
+            .predecessor_account_id(accounts(0))
+            .build());
+        let token_id = "0".to_string();
+        let royalty = HashMap::from([(accounts(2), 1000)]);
+        contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata(), None, Some(royalty));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(accounts(0))
+            .build());
+        let payout = contract.nft_transfer_payout(accounts(1), token_id.clone(), None, None, U128(1000), None).payout;
+        assert_eq!(payout[&accounts(0)], U128(900));
+        assert_eq!(payout[&accounts(2)], U128(100));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .account_balance(env::account_balance())
+            .is_view(true)
+            .attached_deposit(0)
+            .build());
+        assert_eq!(contract.nft_token(token_id).unwrap().owner_id, accounts(1));
+    }
+
+    #[test]
+    fn test_minter_mint() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        contract.add_minter(accounts(2));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST)
+            .predecessor_account_id(accounts(2))
+            .build());
+        let token_id = "0".to_string();
+        let token = contract.nft_mint(token_id.clone(), accounts(1), sample_token_metadata(), None, None);
+        assert_eq!(token.owner_id.to_string(), accounts(1).to_string());
+        assert_eq!(contract.get_minters(None, None), vec![accounts(2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: Only owner or minter can mint")]
+    fn test_mint_not_minter() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST)
+            .predecessor_account_id(accounts(2))
+            .build());
+        contract.nft_mint("0".to_string(), accounts(1), sample_token_metadata(), None, None);
+    }
+
+    #[test]
+    fn test_transfer_ownership() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        contract.transfer_ownership(accounts(1));
+        assert_eq!(contract.get_pending_owner(), Some(accounts(1)));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(accounts(1))
+            .build());
+        contract.accept_ownership();
+        assert_eq!(contract.get_owner(), accounts(1));
+        assert_eq!(contract.get_pending_owner(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: Only owner can update metadata")]
+    fn test_update_metadata_not_owner() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .predecessor_account_id(accounts(1))
+            .build());
+        contract.nft_update_metadata(contract.nft_metadata());
+    }
+
+    #[test]
+    fn test_update_metadata() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(1)
+            .build());
+        let mut metadata = contract.nft_metadata();
+        metadata.base_uri = Some("https://example.com".to_string());
+        contract.nft_update_metadata(metadata.clone());
+        assert_eq!(contract.nft_metadata(), metadata);
+    }
+
+    #[test]
+    fn test_update_token_metadata() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST)
+            .build());
+        let token_id = "0".to_string();
+        contract.nft_mint(token_id.clone(), accounts(1), sample_token_metadata(), None, None);
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST)
+            .build());
+        let mut token_metadata = sample_token_metadata();
+        token_metadata.title = Some("Olympus Mons, the tallest mountain".into());
+        contract.nft_update_token_metadata(token_id.clone(), token_metadata.clone(), None, None);
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .account_balance(env::account_balance())
+            .is_view(true)
+            .attached_deposit(0)
+            .build());
+        assert_eq!(contract.nft_token(token_id).unwrap().metadata.unwrap(), token_metadata);
+    }
+
+    #[test]
+    fn test_update_token_royalty() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST * 2)
+            .build());
+        let token_id = "0".to_string();
+        contract.nft_mint(token_id.clone(), accounts(1), sample_token_metadata(), None, Some(HashMap::from([(accounts(2), 500)])));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST)
+            .build());
+        let royalty = HashMap::from([(accounts(3), 250)]);
+        contract.nft_update_token_metadata(token_id.clone(), sample_token_metadata(), None, Some(royalty.clone()));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .account_balance(env::account_balance())
+            .is_view(true)
+            .attached_deposit(0)
+            .build());
+        assert_eq!(contract.nft_royalties(token_id), royalty);
+    }
+
+    #[test]
+    fn test_update_token_empty_royalty() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = Contract::new_default_meta(accounts(0).into());
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST * 2)
+            .build());
+        let token_id = "0".to_string();
+        contract.nft_mint(token_id.clone(), accounts(1), sample_token_metadata(), None, Some(HashMap::from([(accounts(2), 500)])));
+
+        testing_env!(context
+            .storage_usage(env::storage_usage())
+            .attached_deposit(MINT_STORAGE_COST)
+            .build());
+        contract.nft_update_token_metadata(token_id.clone(), sample_token_metadata(), None, Some(HashMap::new()));
+
+        assert!(contract.royalties.get(&token_id).is_none());
+    }
+
     #[test]
     fn test_transfer() {
         let mut context = get_context(accounts(0));
@@ -207,7 +638,7 @@ mod tests {
             .predecessor_account_id(accounts(0))
             .build());
         let token_id = "0".to_string();
-        contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata());
+        contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata(), None, None);
 
         testing_env!(context
             .storage_usage(env::storage_usage())
@@ -244,7 +675,7 @@ mod tests {
             .predecessor_account_id(accounts(0))
             .build());
         let token_id = "0".to_string();
-        contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata());
+        contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata(), None, None);
 
         // alice approves bob
         testing_env!(context
@@ -275,7 +706,7 @@ mod tests {
             .predecessor_account_id(accounts(0))
             .build());
         let token_id = "0".to_string();
-        contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata());
+        contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata(), None, None);
 
         // alice approves bob
         testing_env!(context
@@ -313,7 +744,7 @@ mod tests {
             .predecessor_account_id(accounts(0))
             .build());
         let token_id = "0".to_string();
-        contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata());
+        contract.nft_mint(token_id.clone(), accounts(0), sample_token_metadata(), None, None);
 
         // alice approves bob
         testing_env!(context
@@ -338,4 +769,34 @@ mod tests {
             .build());
         assert!(!contract.nft_is_approved(token_id.clone(), accounts(1), Some(1)));
     }
+
+    #[test]
+    fn test_migrate_old_state() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let tokens = NonFungibleToken::new(
+            StorageKey::NonFungibleToken,
+            accounts(1),
+            Some(StorageKey::TokenMetadata),
+            Some(StorageKey::Enumeration),
+            Some(StorageKey::Approval),
+        );
+        let metadata = NFTContractMetadata {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: "Old collection".to_string(),
+            symbol: "OLD".to_string(),
+            icon: None,
+            base_uri: None,
+            reference: None,
+            reference_hash: None,
+        };
+        let state = (tokens, LazyOption::new(StorageKey::Metadata, Some(&metadata)));
+        env::storage_write(b"STATE", &state.try_to_vec().unwrap());
+
+        let contract = Contract::migrate();
+        assert_eq!(contract.tokens.owner_id, accounts(1));
+        assert_eq!(contract.nft_metadata().symbol, "OLD");
+        assert!(contract.pending_owner_id.is_none());
+        assert!(contract.minters.is_empty());
+    }
 }
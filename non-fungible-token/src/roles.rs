@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use near_contract_standards::non_fungible_token::refund_deposit_to_account;
+use near_sdk::{AccountId, assert_one_yocto, env, near_bindgen, require};
+use near_sdk::serde_json::json;
+
+use crate::*;
+use crate::events::emit_role_event;
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_owner(&self) -> AccountId {
+        self.tokens.owner_id.clone()
+    }
+
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner_id.clone()
+    }
+
+    /// Starts the ownership transfer, it is completed when the new owner calls `accept_ownership`.
+    #[payable]
+    pub fn transfer_ownership(&mut self, new_owner_id: AccountId) {
+        self.internal_assert_owner();
+
+        self.pending_owner_id = Some(new_owner_id.clone());
+        emit_role_event("ownership_transfer_started", json!({
+            "owner_id": self.tokens.owner_id,
+            "pending_owner_id": new_owner_id,
+        }));
+    }
+
+    #[payable]
+    pub fn accept_ownership(&mut self) {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        require!(self.pending_owner_id.as_ref() == Some(&owner_id), "Unauthorized: Only pending owner can accept ownership");
+
+        let previous_owner_id = std::mem::replace(&mut self.tokens.owner_id, owner_id.clone());
+        self.pending_owner_id = None;
+        emit_role_event("ownership_transferred", json!({
+            "previous_owner_id": previous_owner_id,
+            "owner_id": owner_id,
+        }));
+    }
+
+    #[payable]
+    pub fn add_minter(&mut self, account_id: AccountId) {
+        self.internal_assert_owner();
+
+        require!(self.minters.insert(&account_id), "Minter already exists");
+        emit_role_event("minter_added", json!({
+            "account_id": account_id,
+        }));
+    }
+
+    #[payable]
+    pub fn remove_minter(&mut self, account_id: AccountId) {
+        self.internal_assert_owner();
+
+        require!(self.minters.remove(&account_id), "Minter not found");
+        emit_role_event("minter_removed", json!({
+            "account_id": account_id,
+        }));
+    }
+
+    pub fn is_minter(&self, account_id: AccountId) -> bool {
+        self.minters.contains(&account_id)
+    }
+
+    pub fn get_minters(&self, from_index: Option<u64>, limit: Option<u64>) -> Vec<AccountId> {
+        self.minters.iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u64::MAX) as usize)
+            .collect()
+    }
+
+    /// Replaces the contract metadata, for example its `base_uri` or `icon`.
+    #[payable]
+    pub fn nft_update_metadata(&mut self, metadata: NFTContractMetadata) {
+        assert_one_yocto();
+        require!(env::predecessor_account_id() == self.tokens.owner_id, "Unauthorized: Only owner can update metadata");
+        metadata.assert_valid();
+
+        self.metadata.set(&metadata);
+        emit_role_event("contract_metadata_updated", json!({
+            "owner_id": self.tokens.owner_id,
+        }));
+    }
+
+    /// Replaces the metadata of an existing token with the one from its origin chain, the royalty
+    /// is replaced too if it is set and removed if it is empty. Can be called by the owner or a minter. The attached deposit
+    /// has to cover the additional storage, the surplus is refunded to `refund_id` or to the caller
+    /// if it is not set.
+    #[payable]
+    pub fn nft_update_token_metadata(
+        &mut self,
+        token_id: TokenId,
+        token_metadata: TokenMetadata,
+        refund_id: Option<AccountId>,
+        royalty: Option<HashMap<AccountId, u32>>,
+    ) {
+        self.internal_assert_minter();
+        require!(self.tokens.owner_by_id.get(&token_id).is_some(), "Token not found");
+
+        let initial_storage_usage = env::storage_usage();
+        if let Some(token_metadata_by_id) = &mut self.tokens.token_metadata_by_id {
+            token_metadata_by_id.insert(&token_id, &token_metadata);
+        }
+        match royalty {
+            Some(royalty) if royalty.is_empty() => {
+                self.royalties.remove(&token_id);
+            }
+            Some(royalty) => {
+                Self::internal_assert_royalty(&royalty);
+                self.royalties.insert(&token_id, &royalty);
+            }
+            None => {}
+        }
+
+        refund_deposit_to_account(
+            env::storage_usage().saturating_sub(initial_storage_usage),
+            refund_id.unwrap_or_else(env::predecessor_account_id),
+        );
+        emit_role_event("token_metadata_updated", json!({
+            "token_id": token_id,
+        }));
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_assert_owner(&self) {
+        assert_one_yocto();
+        require!(env::predecessor_account_id() == self.tokens.owner_id, "Unauthorized: Only owner can manage roles");
+    }
+
+    pub(crate) fn internal_assert_minter(&self) {
+        let account_id = env::predecessor_account_id();
+        require!(
+            account_id == self.tokens.owner_id || self.minters.contains(&account_id),
+            "Unauthorized: Only owner or minter can mint",
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use near_contract_standards::non_fungible_token::TokenId;
+use near_contract_standards::non_fungible_token::refund_approved_account_ids;
+use near_sdk::{AccountId, assert_one_yocto, env, near_bindgen, require};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// Royalties are set in basis points of the sale price.
+pub const ROYALTY_DENOMINATOR: u32 = 10_000;
+pub const MAX_ROYALTY_ACCOUNTS: usize = 10;
+
+/// Amounts the marketplace sends out of the sale price, as specified by NEP-199.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Payout {
+    pub payout: HashMap<AccountId, U128>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Returns the royalty shares of the token in basis points.
+    pub fn nft_royalties(&self, token_id: TokenId) -> HashMap<AccountId, u32> {
+        require!(self.tokens.owner_by_id.get(&token_id).is_some(), "Token not found");
+        self.royalties.get(&token_id).unwrap_or_default()
+    }
+
+    /// Splits `balance` between the royalty accounts of the token and its owner, who gets the rest.
+    pub fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: Option<u32>) -> Payout {
+        let owner_id = self.tokens.owner_by_id.get(&token_id).unwrap_or_else(|| env::panic_str("Token not found"));
+        self.internal_payout(&token_id, &owner_id, balance.0, max_len_payout)
+    }
+
+    /// Transfers the token like `nft_transfer` and returns the payout of the sale to the previous owner.
+    #[payable]
+    pub fn nft_transfer_payout(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: Option<u32>,
+    ) -> Payout {
+        assert_one_yocto();
+        let owner_id = self.tokens.owner_by_id.get(&token_id).unwrap_or_else(|| env::panic_str("Token not found"));
+        let payout = self.internal_payout(&token_id, &owner_id, balance.0, max_len_payout);
+
+        let (previous_owner_id, approved_account_ids) = self.tokens.internal_transfer(
+            &env::predecessor_account_id(),
+            &receiver_id,
+            &token_id,
+            approval_id,
+            memo,
+        );
+        if let Some(approved_account_ids) = approved_account_ids {
+            refund_approved_account_ids(previous_owner_id, &approved_account_ids);
+        }
+
+        payout
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_assert_royalty(royalty: &HashMap<AccountId, u32>) {
+        require!(royalty.len() <= MAX_ROYALTY_ACCOUNTS, format!("Royalty can not have more than {} accounts", MAX_ROYALTY_ACCOUNTS));
+
+        let total = royalty.values().fold(0u64, |total, share| total + u64::from(*share));
+        require!(total <= u64::from(ROYALTY_DENOMINATOR), "Royalty can not exceed 100%");
+    }
+
+    fn internal_payout(&self, token_id: &TokenId, owner_id: &AccountId, balance: u128, max_len_payout: Option<u32>) -> Payout {
+        let royalty = self.royalties.get(token_id).unwrap_or_default();
+
+        let mut payout = HashMap::new();
+        let mut paid = 0;
+        for (account_id, share) in royalty {
+            let amount = balance / u128::from(ROYALTY_DENOMINATOR) * u128::from(share)
+                + balance % u128::from(ROYALTY_DENOMINATOR) * u128::from(share) / u128::from(ROYALTY_DENOMINATOR);
+            paid += amount;
+            payout.insert(account_id, amount);
+        }
+        *payout.entry(owner_id.clone()).or_insert(0) += balance - paid;
+
+        if let Some(max_len_payout) = max_len_payout {
+            require!(payout.len() <= max_len_payout as usize, "Payout exceeds max_len_payout");
+        }
+
+        Payout {
+            payout: payout.into_iter().map(|(account_id, amount)| (account_id, U128(amount))).collect(),
+        }
+    }
+}
@@ -26,7 +26,7 @@ near deploy --wasmFile .././res/feer.wasm --accountId $FEE
 # initialize contracts
 CHAIN="Near"
 
-near call $BRIDGE new '{"signer": "'$SIGNER_PUB_KEY'", "fee_contract": "'$FEE'", "chain": "'$CHAIN'"}' --accountId $ID
+near call $BRIDGE new '{"signers": ["'$SIGNER_PUB_KEY'"], "threshold": 1, "fee_contract": "'$FEE'", "chain": "'$CHAIN'"}' --accountId $ID
 near call $FEE new '{"chain": "'$CHAIN'", "bridge_addr": "'$BRIDGE'", "tokens": [{"token_type": "Native", "fee": "1"}, {"token_addr": "'$USDC'", "token_type": "FT", "fee": "1"}]}' --accountId $ID
 near call $USDC storage_deposit '{"account_id": "'$FEE'"}' --accountId $ID --amount 0.00125
 near call $FEE storage_deposit '{"account_id": "'$ID'"}' --accountId $ID --amount 0.00663
@@ -0,0 +1,70 @@
+use near_sdk::{AccountId, env, Gas};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::{Data, decode_hex_to_vec, to_32_bytes, u128_to_bytes, usize_to_bytes, vector_from_32_bytes};
+
+/// Gas reserved for every step of the bundle execution on the proxy.
+pub const GAS_FOR_BUNDLE_STEP: Gas = Gas(10_000_000_000_000);
+/// Length of the proxy account name prefix, in bytes of the derivation hash.
+const PROXY_NAME_LENGTH: usize = 16;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BundleCall {
+    pub contract: AccountId,
+    pub method: String,
+    pub args: Base64VecU8,
+    pub deposit: U128,
+    pub gas: U64,
+}
+
+/// Function calls executed by the bundle proxy with the withdrawn assets.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Bundle {
+    // Hex encoded salt of the proxy account
+    pub salt: String,
+    pub calls: Vec<BundleCall>,
+}
+
+impl Bundle {
+    /// Gas the proxy needs to run all the calls one after another.
+    pub fn required_gas(&self) -> Gas {
+        self.calls.iter().fold(GAS_FOR_BUNDLE_STEP, |gas, call| gas + Gas(call.gas.0) + GAS_FOR_BUNDLE_STEP)
+    }
+
+    /// Returns the proxy account that receives the assets and executes the bundle for the receiver.
+    pub fn get_proxy_id(&self, bridge: &AccountId, receiver: &AccountId) -> AccountId {
+        get_bundle_proxy_id(bridge, receiver, &self.salt)
+    }
+}
+
+impl Data for Bundle {
+    fn get_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+
+        data.append(&mut to_32_bytes(decode_hex_to_vec(self.salt.clone()).as_slice()));
+
+        for call in self.calls.iter() {
+            for bytes in [call.contract.as_bytes(), call.method.as_bytes(), call.args.0.as_slice()] {
+                data.append(&mut vector_from_32_bytes(usize_to_bytes(bytes.len())));
+                data.append(&mut bytes.to_vec());
+            }
+
+            data.append(&mut vector_from_32_bytes(u128_to_bytes(call.deposit)));
+            data.append(&mut vector_from_32_bytes(u128_to_bytes(U128(call.gas.0 as u128))));
+        }
+
+        data
+    }
+}
+
+/// Derives the proxy sub-account `<hex(keccak256(salt || receiver))[..16 bytes]>.<bridge>`.
+pub fn get_bundle_proxy_id(bridge: &AccountId, receiver: &AccountId, salt: &str) -> AccountId {
+    let hash = env::keccak256([decode_hex_to_vec(salt.to_string()).as_slice(), receiver.as_bytes()].concat().as_slice());
+    let name = hash[..PROXY_NAME_LENGTH].iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+
+    AccountId::new_unchecked(format!("{}.{}", name, bridge))
+}
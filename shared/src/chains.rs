@@ -0,0 +1,127 @@
+use std::str::FromStr;
+
+use near_sdk::{AccountId, env};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::serde::{Deserialize, Serialize};
+
+const EVM_ADDRESS_LENGTH: usize = 20;
+const SOLANA_ADDRESS_LENGTH: usize = 32;
+const RARIMO_ADDRESS_PREFIX: &str = "rarimo";
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AddressFormat {
+    /// 20-byte hex address with the `0x` prefix and an optional EIP-55 checksum.
+    Evm,
+    /// Base58 encoded 32-byte public key.
+    Solana,
+    /// NEAR `AccountId`.
+    Near,
+    /// Bech32 address with the `rarimo` prefix.
+    Rarimo,
+}
+
+impl AddressFormat {
+    /// Returns the canonical form of the address or `None` if it does not match the format.
+    pub fn normalize(&self, address: &str) -> Option<String> {
+        match self {
+            AddressFormat::Evm => normalize_evm_address(address),
+            AddressFormat::Solana => {
+                let bytes = bs58::decode(address).into_vec().ok()?;
+                if bytes.len() != SOLANA_ADDRESS_LENGTH {
+                    return None;
+                }
+                Some(bs58::encode(bytes).into_string())
+            }
+            AddressFormat::Near => AccountId::from_str(address).ok().map(|account| account.to_string()),
+            AddressFormat::Rarimo => {
+                let (hrp, _, _) = bech32::decode(address).ok()?;
+                if hrp != RARIMO_ADDRESS_PREFIX {
+                    return None;
+                }
+                Some(address.to_lowercase())
+            }
+        }
+    }
+}
+
+fn normalize_evm_address(address: &str) -> Option<String> {
+    let hex = address.strip_prefix("0x")?;
+    if hex.len() != EVM_ADDRESS_LENGTH * 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let checksummed = to_checksum_address(hex.to_lowercase().as_str());
+
+    // Single case addresses carry no checksum, mixed case ones must match it
+    let is_single_case = hex == hex.to_lowercase() || hex == hex.to_uppercase();
+    if !is_single_case && hex != checksummed {
+        return None;
+    }
+
+    Some(format!("0x{}", checksummed))
+}
+
+/// EIP-55 mixed case checksum encoding of the lowercase hex address without the prefix.
+fn to_checksum_address(hex: &str) -> String {
+    let hash = env::keccak256(hex.as_bytes());
+
+    hex.chars().enumerate().map(|(i, c)| {
+        let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+        if c.is_ascii_alphabetic() && nibble >= 8 { c.to_ascii_uppercase() } else { c }
+    }).collect()
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ChainInfo {
+    pub chain: String,
+    pub format: AddressFormat,
+}
+
+/// Supported destination chains with the address format of their receivers.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Chains {
+    pub chains: UnorderedMap<String, AddressFormat>,
+}
+
+impl Default for Chains {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chains {
+    pub fn new() -> Self {
+        Self {
+            chains: UnorderedMap::new(b"chains".to_vec()),
+        }
+    }
+
+    pub fn set_chain(&mut self, chain: &String, format: Option<AddressFormat>) {
+        match format {
+            Some(format) => {
+                assert_ne!(chain, "", "Chains: chain name is required");
+                self.chains.insert(chain, &format);
+            }
+            None => {
+                assert!(self.chains.remove(chain).is_some(), "Chains: chain is not supported");
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chains.is_empty()
+    }
+
+    pub fn get_chains(&self) -> Vec<ChainInfo> {
+        self.chains.iter().map(|(chain, format)| ChainInfo { chain, format }).collect()
+    }
+
+    /// Returns the normalized receiver address or `None` if the chain is not supported or the
+    /// receiver does not match its address format.
+    pub fn normalize_receiver(&self, chain: &String, receiver: &str) -> Option<String> {
+        self.chains.get(chain)?.normalize(receiver)
+    }
+}
@@ -4,6 +4,10 @@ pub mod merkle;
 pub mod types;
 pub mod bytes;
 pub mod log;
+pub mod signers;
+pub mod chains;
+pub mod bundle;
+pub mod operations;
 
 pub use constants::*;
 pub use hashes::*;
@@ -11,3 +15,7 @@ pub use merkle::*;
 pub use types::*;
 pub use bytes::*;
 pub use log::*;
+pub use signers::*;
+pub use chains::*;
+pub use bundle::*;
+pub use operations::*;
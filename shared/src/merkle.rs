@@ -57,12 +57,16 @@ pub fn get_merkle_root(content: ContentNode, path: &Vec<Hash>) -> Hash {
     return Hash::from_slice(hash.as_slice());
 }
 
-pub fn verify_ecdsa_signature(signer: SignerPublicKey, msg: Hash, signature: Secp256K1Signature, recovery_id: RecoveryID) {
+pub fn recover_ecdsa_signer(msg: Hash, signature: Secp256K1Signature, recovery_id: RecoveryID) -> SignerPublicKey {
     let public_key = env::ecrecover(msg.to_slice(), signature.as_slice(), recovery_id.unwrap(), true);
     if public_key.is_none() {
         env::panic_str("Signer: invalid signature, public recovered key is none");
     }
 
-    assert_eq!(signer, SignerPublicKey::new(bs58::encode(public_key.unwrap()).into_string()), "Signer: invalid signature, public recovered key is not equal to signer public key");
+    SignerPublicKey::new(bs58::encode(public_key.unwrap()).into_string())
 }
 
+pub fn verify_ecdsa_signature(signer: SignerPublicKey, msg: Hash, signature: Secp256K1Signature, recovery_id: RecoveryID) {
+    let public_key = recover_ecdsa_signer(msg, signature, recovery_id);
+    assert_eq!(signer, public_key, "Signer: invalid signature, public recovered key is not equal to signer public key");
+}
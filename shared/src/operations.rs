@@ -0,0 +1,107 @@
+use near_sdk::{AccountId, env};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+
+use crate::{Hash, u128_to_bytes, usize_to_bytes, vector_from_32_bytes};
+
+/// Domain separator of the authorized operation hashes, never collides with a Merkle root or a
+/// withdrawal content hash.
+pub const OPERATION_DOMAIN: &str = "RarimoNearAuthorizedOperation";
+
+/// Type tag of an operation authorized by the signers, it is the first field of the signed data.
+#[derive(BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[repr(u8)]
+pub enum OperationType {
+    SetFeeContract,
+    UpdateContract,
+    AddSigner,
+    RemoveSigner,
+    SetThreshold,
+    Pause,
+    Resume,
+    AddToken,
+    RemoveToken,
+    StoreTokenCode,
+    StoreProxyCode,
+    DeployWrappedFT,
+    DeployWrappedNFT,
+    SetTokenLimits,
+    SetTokenTimelock,
+    AddGuardian,
+    RemoveGuardian,
+    PauseScope,
+    ResumeScope,
+    SetChain,
+    RemoveChain,
+    SetFallbackReceiver,
+    AddFeeToken,
+    UpdateFeeToken,
+    RemoveFeeToken,
+    WithdrawFees,
+    AnnounceMigration,
+    CancelMigration,
+    SetMigrationDelay,
+    MigrateAssets,
+    RescueTokens,
+    SetMintCap,
+    UpdateWrappedNFTMetadata,
+    UpdateWrappedNFTTokenMetadata,
+    SeedReserves,
+    SeedHeldTokens,
+}
+
+/// Typed operation data, every payload field is encoded as its 32-byte length followed by its bytes.
+///
+/// The signed hash is
+/// `keccak256(keccak256(domain) || type || keccak256(contract) || keccak256(chain) || nonce || keccak256(payload))`
+/// where `type` and `nonce` are 32-byte big-endian numbers.
+#[derive(Debug, Clone)]
+pub struct AuthorizedOperation {
+    pub operation_type: OperationType,
+    pub payload: Vec<u8>,
+}
+
+impl AuthorizedOperation {
+    pub fn new(operation_type: OperationType) -> Self {
+        Self {
+            operation_type,
+            payload: Vec::new(),
+        }
+    }
+
+    pub fn bytes(mut self, bytes: &[u8]) -> Self {
+        self.payload.append(&mut vector_from_32_bytes(usize_to_bytes(bytes.len())));
+        self.payload.append(&mut bytes.to_vec());
+        self
+    }
+
+    pub fn string(self, value: &str) -> Self {
+        self.bytes(value.as_bytes())
+    }
+
+    pub fn account(self, account: &AccountId) -> Self {
+        self.bytes(account.as_bytes())
+    }
+
+    pub fn number(self, value: u128) -> Self {
+        self.bytes(u128_to_bytes(U128(value)).as_slice())
+    }
+
+    /// Structured values are encoded with Borsh.
+    pub fn borsh<T: BorshSerialize>(self, value: &T) -> Self {
+        self.bytes(value.try_to_vec().unwrap().as_slice())
+    }
+
+    pub fn hash(&self, contract: &AccountId, chain: &str, nonce: u128) -> Hash {
+        let mut data = Vec::new();
+
+        data.append(&mut env::keccak256(OPERATION_DOMAIN.as_bytes()));
+        data.append(&mut vector_from_32_bytes(usize_to_bytes(self.operation_type as usize)));
+        data.append(&mut env::keccak256(contract.as_bytes()));
+        data.append(&mut env::keccak256(chain.as_bytes()));
+        data.append(&mut vector_from_32_bytes(u128_to_bytes(U128(nonce))));
+        data.append(&mut env::keccak256(self.payload.as_slice()));
+
+        Hash::from_slice(env::keccak256(data.as_slice()).as_slice())
+    }
+}
@@ -0,0 +1,82 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::env;
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::merkle::recover_ecdsa_signer;
+use crate::types::{Hash, RecoveryID, Secp256K1Signature, SignerPublicKey};
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RecoverableSignature {
+    pub signature: String,
+    pub recovery_id: RecoveryID,
+}
+
+/// The k-of-n set of secp256k1 keys which authorizes bridge operations.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SignerSet {
+    pub signers: Vec<SignerPublicKey>,
+    pub threshold: u32,
+}
+
+impl SignerSet {
+    pub fn new(signers: Vec<SignerPublicKey>, threshold: u32) -> Self {
+        let mut set = Self {
+            signers: Vec::new(),
+            threshold: 0,
+        };
+
+        for signer in signers {
+            set.add_signer(signer);
+        }
+
+        set.set_threshold(threshold);
+        set
+    }
+
+    pub fn is_member(&self, signer: &SignerPublicKey) -> bool {
+        self.signers.contains(signer)
+    }
+
+    pub fn add_signer(&mut self, signer: SignerPublicKey) {
+        if !signer.validate() {
+            env::panic_str("Signers: invalid signer public key");
+        }
+
+        assert!(!self.is_member(&signer), "Signers: signer already exists");
+        self.signers.push(signer);
+    }
+
+    pub fn remove_signer(&mut self, signer: SignerPublicKey) {
+        assert!(self.is_member(&signer), "Signers: signer not found");
+        assert!(self.signers.len() as u32 > self.threshold, "Signers: removing the signer makes threshold unreachable");
+        self.signers.retain(|s| *s != signer);
+    }
+
+    pub fn set_threshold(&mut self, threshold: u32) {
+        assert!(threshold > 0, "Signers: threshold must be greater than zero");
+        assert!(threshold <= self.signers.len() as u32, "Signers: threshold is greater than the number of signers");
+        self.threshold = threshold;
+    }
+
+    /// Panics unless at least `threshold` distinct members of the set signed `msg`.
+    pub fn verify(&self, msg: Hash, signatures: &[RecoverableSignature]) {
+        let mut approvals: Vec<SignerPublicKey> = Vec::new();
+
+        for signature in signatures.iter() {
+            let signer = recover_ecdsa_signer(
+                msg,
+                Secp256K1Signature::from_hex(signature.signature.clone()),
+                signature.recovery_id.clone(),
+            );
+
+            assert!(self.is_member(&signer), "Signers: recovered key is not a member of the signer set");
+            assert!(!approvals.contains(&signer), "Signers: duplicated signature");
+
+            approvals.push(signer);
+        }
+
+        assert!(approvals.len() as u32 >= self.threshold, "Signers: not enough signatures");
+    }
+}